@@ -0,0 +1,291 @@
+use anyhow::Context;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use crate::repo;
+
+/// Directories under `$HOME` that a starter config lists as repo roots when they exist.
+const COMMON_REPO_ROOTS: &[&str] = &["code", "src", "projects", "dev", "repos", "git"];
+
+pub(crate) struct DoctorRequest {
+    pub(crate) config_path: Option<PathBuf>,
+    pub(crate) cache_path: Option<PathBuf>,
+    /// Apply the safe fixes for the problems found (`--fix`).
+    pub(crate) fix: bool,
+    /// Apply them without asking (`--yes`).
+    pub(crate) yes: bool,
+}
+
+/// A problem `w doctor` found, and the non-destructive fix for it (if it has one).
+struct Problem {
+    message: String,
+    fix: Option<Fix>,
+}
+
+enum Fix {
+    /// Create the config file (and its directory) with a starter config.
+    CreateConfig {
+        path: PathBuf,
+    },
+    CreateCacheDir {
+        path: PathBuf,
+    },
+    /// Move an unreadable repo index aside and scan again.
+    RebuildCache {
+        cache_path: PathBuf,
+        config_path: Option<PathBuf>,
+    },
+}
+
+impl Fix {
+    fn describe(&self) -> String {
+        match self {
+            Fix::CreateConfig { path } => format!("create a starter config at {}", path.display()),
+            Fix::CreateCacheDir { path } => {
+                format!("create the cache directory {}", path.display())
+            }
+            Fix::RebuildCache { cache_path, .. } => format!(
+                "move {} aside to {} and rebuild the repo index",
+                cache_path.display(),
+                corrupt_backup_path(cache_path).display()
+            ),
+        }
+    }
+
+    /// Apply the fix, returning what was done.
+    fn apply(&self) -> anyhow::Result<String> {
+        match self {
+            Fix::CreateConfig { path } => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)
+                        .with_context(|| format!("failed to create {}", parent.display()))?;
+                }
+                let roots = starter_repo_roots();
+                // `create_new` so a config written since the check is never overwritten.
+                std::fs::OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(path)
+                    .and_then(|mut file| file.write_all(starter_config(&roots).as_bytes()))
+                    .with_context(|| format!("failed to write {}", path.display()))?;
+                Ok(if roots.is_empty() {
+                    format!(
+                        "created {}; set repo_roots in it to the directories holding your repos",
+                        path.display()
+                    )
+                } else {
+                    format!(
+                        "created {} with repo_roots = [{}]",
+                        path.display(),
+                        roots.join(", ")
+                    )
+                })
+            }
+            Fix::CreateCacheDir { path } => {
+                std::fs::create_dir_all(path)
+                    .with_context(|| format!("failed to create {}", path.display()))?;
+                Ok(format!("created {}", path.display()))
+            }
+            Fix::RebuildCache {
+                cache_path,
+                config_path,
+            } => {
+                let backup = corrupt_backup_path(cache_path);
+                std::fs::rename(cache_path, &backup)
+                    .with_context(|| format!("failed to move {} aside", cache_path.display()))?;
+                let moved = format!("moved {} to {}", cache_path.display(), backup.display());
+                let rebuilt =
+                    crate::repo_scan_settings(config_path.as_deref(), Vec::new(), None, false)
+                        .and_then(|(roots, options)| {
+                            repo::build_repo_index(&roots, options, cache_path, false)
+                        });
+                Ok(match rebuilt {
+                    Ok(index) => format!(
+                        "{moved}; rebuilt the repo index ({} repos)",
+                        index.repos.len()
+                    ),
+                    Err(err) => format!(
+                        "{moved}; the next `w ls` rebuilds it (rebuilding now failed: {err:#})"
+                    ),
+                })
+            }
+        }
+    }
+}
+
+/// `w doctor [--fix]`: check w's config and cache, optionally fixing what's safe to fix.
+///
+/// Returns whether no problems remain.
+pub(crate) fn doctor(request: DoctorRequest) -> anyhow::Result<bool> {
+    let config_path = match &request.config_path {
+        Some(path) => path.clone(),
+        None => repo::default_config_path()?,
+    };
+    let cache_path = match &request.cache_path {
+        Some(path) => path.clone(),
+        None => repo::default_cache_path()?,
+    };
+
+    let mut problems = Vec::new();
+    check_config(&config_path, &mut problems);
+    check_cache(&cache_path, request.config_path.as_deref(), &mut problems);
+
+    if !request.fix {
+        for problem in &problems {
+            let hint = if problem.fix.is_some() {
+                " (fix: w doctor --fix)"
+            } else {
+                ""
+            };
+            println!("problem: {}{hint}", problem.message);
+        }
+        return Ok(problems.is_empty());
+    }
+
+    let (fixable, unfixable): (Vec<_>, Vec<_>) = problems
+        .into_iter()
+        .partition(|problem| problem.fix.is_some());
+    if !fixable.is_empty() {
+        for problem in &fixable {
+            eprintln!("problem: {}", problem.message);
+            if let Some(fix) = &problem.fix {
+                eprintln!("  will {}", fix.describe());
+            }
+        }
+        if !request.yes && !confirm("Apply these fixes?")? {
+            anyhow::bail!("no changes made");
+        }
+        for fix in fixable.iter().filter_map(|problem| problem.fix.as_ref()) {
+            println!("fixed: {}", fix.apply()?);
+        }
+    }
+    for problem in &unfixable {
+        println!("problem: {} (not fixed automatically)", problem.message);
+    }
+    Ok(unfixable.is_empty())
+}
+
+fn check_config(config_path: &Path, problems: &mut Vec<Problem>) {
+    if !config_path.exists() {
+        problems.push(Problem {
+            message: format!("config file {} does not exist", config_path.display()),
+            fix: Some(Fix::CreateConfig {
+                path: config_path.to_path_buf(),
+            }),
+        });
+        return;
+    }
+    let config = match repo::load_config(config_path) {
+        Ok(config) => config,
+        Err(err) => {
+            problems.push(Problem {
+                message: format!("{err:#}"),
+                fix: None,
+            });
+            return;
+        }
+    };
+    let found = problems.len();
+    if config.repo_roots.is_empty() {
+        problems.push(Problem {
+            message: format!(
+                "no repo roots configured (set repo_roots in {})",
+                config_path.display()
+            ),
+            fix: None,
+        });
+    }
+    for root in config.repo_roots.iter().filter(|root| !root.is_dir()) {
+        problems.push(Problem {
+            message: format!("repo root {} does not exist", root.display()),
+            fix: None,
+        });
+    }
+    if problems.len() == found {
+        println!(
+            "ok: config {} ({} repo roots)",
+            config_path.display(),
+            config.repo_roots.len()
+        );
+    }
+}
+
+fn check_cache(cache_path: &Path, config_path: Option<&Path>, problems: &mut Vec<Problem>) {
+    if let Some(cache_dir) = cache_path.parent()
+        && !cache_dir.is_dir()
+    {
+        problems.push(Problem {
+            message: format!("cache directory {} does not exist", cache_dir.display()),
+            fix: Some(Fix::CreateCacheDir {
+                path: cache_dir.to_path_buf(),
+            }),
+        });
+        return;
+    }
+    if !cache_path.exists() {
+        println!(
+            "ok: repo index {} not built yet (`w repo index` builds it)",
+            cache_path.display()
+        );
+        return;
+    }
+    match repo::read_repo_index_cache(cache_path) {
+        Ok(index) => println!(
+            "ok: repo index {} ({} repos{})",
+            cache_path.display(),
+            index.repos.len(),
+            if index.complete { "" } else { ", incomplete" }
+        ),
+        Err(err) => problems.push(Problem {
+            message: format!("{err:#}"),
+            fix: Some(Fix::RebuildCache {
+                cache_path: cache_path.to_path_buf(),
+                config_path: config_path.map(Path::to_path_buf),
+            }),
+        }),
+    }
+}
+
+/// Ask on stderr; without a terminal to ask on, require `--yes` instead.
+fn confirm(question: &str) -> anyhow::Result<bool> {
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!("w doctor --fix: pass --yes to apply fixes without a terminal to confirm on");
+    }
+    eprint!("{question} [y/N] ");
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// `~/<dir>` for each of `COMMON_REPO_ROOTS` that exists.
+fn starter_repo_roots() -> Vec<String> {
+    let Ok(home) = repo::home_dir() else {
+        return Vec::new();
+    };
+    COMMON_REPO_ROOTS
+        .iter()
+        .filter(|dir| home.join(dir).is_dir())
+        .map(|dir| format!("~/{dir}"))
+        .collect()
+}
+
+fn starter_config(roots: &[String]) -> String {
+    let roots = roots
+        .iter()
+        .map(|root| format!("{root:?}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "# Directories to scan for git repositories (e.g. [\"~/code\"]).\n\
+         repo_roots = [{roots}]\n\
+         # How deep to look for repositories under each root.\n\
+         max_depth = 6\n"
+    )
+}
+
+fn corrupt_backup_path(cache_path: &Path) -> PathBuf {
+    let mut name = cache_path.as_os_str().to_owned();
+    name.push(".corrupt");
+    PathBuf::from(name)
+}
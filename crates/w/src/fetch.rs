@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// When `w fetch` last fetched each repo successfully (`$XDG_CACHE_HOME/w/fetch-state.json`).
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct FetchState {
+    pub(crate) schema_version: u32,
+    /// Unix timestamp (seconds) of the last successful fetch, keyed by canonical repo path.
+    pub(crate) fetched_at: BTreeMap<String, u64>,
+}
+
+pub(crate) fn default_fetch_state_path() -> anyhow::Result<PathBuf> {
+    Ok(crate::repo::w_cache_dir()?.join("fetch-state.json"))
+}
+
+pub(crate) fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl FetchState {
+    /// How long ago `repo_path` was last fetched, if that was within `window`.
+    pub(crate) fn fetched_within(
+        &self,
+        repo_path: &str,
+        window: Duration,
+        now: u64,
+    ) -> Option<u64> {
+        let fetched_at = *self.fetched_at.get(repo_path)?;
+        let age = now.saturating_sub(fetched_at);
+        (age < window.as_secs()).then_some(age)
+    }
+}
+
+/// A missing or unreadable state file is treated as empty: the worst case is fetching
+/// a repo that didn't need it.
+pub(crate) fn read_fetch_state(state_path: &Path) -> FetchState {
+    std::fs::read_to_string(state_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or(FetchState {
+            schema_version: 1,
+            fetched_at: BTreeMap::new(),
+        })
+}
+
+/// Record that `repo_paths` were fetched at `fetched_at`.
+///
+/// The state file is re-read right before it's replaced, so fetches another `w fetch`
+/// recorded while this one ran are kept rather than overwritten with the copy read at
+/// startup.
+pub(crate) fn record_fetches(
+    state_path: &Path,
+    repo_paths: impl IntoIterator<Item = String>,
+    fetched_at: u64,
+) -> anyhow::Result<()> {
+    let mut state = read_fetch_state(state_path);
+    for repo_path in repo_paths {
+        state.fetched_at.insert(repo_path, fetched_at);
+    }
+    crate::repo::write_json_atomically(state_path, &state, "fetch state file")
+}
+
+/// An age in its largest whole unit, e.g. `42s`, `5m`, `3h`, `2d`.
+pub(crate) fn format_age(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
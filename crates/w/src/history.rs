@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Maximum number of worktrees remembered in the history file.
+const HISTORY_LIMIT: usize = 50;
+
+/// Recently visited worktrees, most recent first (`$XDG_CACHE_HOME/w/history.json`).
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct History {
+    pub(crate) schema_version: u32,
+    pub(crate) entries: Vec<HistoryEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct HistoryEntry {
+    pub(crate) path: String,
+    /// Unix timestamp (seconds) of the most recent visit.
+    pub(crate) visited_at: u64,
+}
+
+pub(crate) fn default_history_path() -> anyhow::Result<PathBuf> {
+    Ok(crate::repo::w_cache_dir()?.join("history.json"))
+}
+
+/// Move `path` to the front of the history (adding it if new), dropping the oldest
+/// entries beyond the limit.
+pub(crate) fn record_visit(path: &Path) -> anyhow::Result<()> {
+    let history_path = default_history_path()?;
+    let mut history = read_history(&history_path);
+
+    let path = dunce::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string();
+    let visited_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    history.entries.retain(|entry| entry.path != path);
+    history.entries.insert(0, HistoryEntry { path, visited_at });
+    history.entries.truncate(HISTORY_LIMIT);
+
+    write_history(&history_path, &history)
+}
+
+/// Visited worktrees that still exist, most recent first; vanished paths are pruned
+/// from the history file.
+pub(crate) fn recent_worktrees() -> anyhow::Result<Vec<HistoryEntry>> {
+    let history_path = default_history_path()?;
+    let mut history = read_history(&history_path);
+
+    let before = history.entries.len();
+    history
+        .entries
+        .retain(|entry| Path::new(&entry.path).is_dir());
+    if history.entries.len() != before {
+        write_history(&history_path, &history)?;
+    }
+
+    Ok(history.entries)
+}
+
+/// A missing or unreadable history file is treated as empty: history is a convenience,
+/// not something worth failing a command over.
+fn read_history(history_path: &Path) -> History {
+    std::fs::read_to_string(history_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or(History {
+            schema_version: 1,
+            entries: Vec::new(),
+        })
+}
+
+fn write_history(history_path: &Path, history: &History) -> anyhow::Result<()> {
+    crate::repo::write_json_atomically(history_path, history, "history file")
+}
+
+/// Filter the history the same way `w switch --filter` matches worktrees (case-insensitive
+/// substring of the path).
+pub(crate) fn select_by_filter(entries: &[HistoryEntry], filter: &str) -> Option<PathBuf> {
+    let needle = filter.to_lowercase();
+    entries
+        .iter()
+        .find(|entry| entry.path.to_lowercase().contains(&needle))
+        .map(|entry| PathBuf::from(&entry.path))
+}
+
+#[cfg(windows)]
+pub(crate) fn pick_interactive(
+    _entries: &[HistoryEntry],
+    _picker: &crate::repo::PickerConfig,
+) -> anyhow::Result<Option<PathBuf>> {
+    anyhow::bail!(
+        "interactive picker is not supported on Windows; pass --filter for non-interactive selection"
+    );
+}
+
+#[cfg(not(windows))]
+pub(crate) fn pick_interactive(
+    entries: &[HistoryEntry],
+    picker: &crate::repo::PickerConfig,
+) -> anyhow::Result<Option<PathBuf>> {
+    use std::io::{Cursor, IsTerminal};
+
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!(
+            "interactive picker requires a TTY (stdin); pass --filter for non-interactive selection"
+        );
+    }
+
+    use skim::prelude::*;
+
+    let options = crate::repo::skim_options(picker, "recent> ", 1)?;
+
+    let input = entries
+        .iter()
+        .map(|entry| entry.path.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let items = SkimItemReader::default().of_bufread(Cursor::new(input));
+    let out = Skim::run_with(&options, Some(items)).map(|out| out.selected_items);
+    let Some(selected) = out.and_then(|items| items.into_iter().next()) else {
+        return Ok(None);
+    };
+
+    let path = selected.output().trim().to_string();
+    if path.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(PathBuf::from(path)))
+}
@@ -0,0 +1,280 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use worktrunk::HookType;
+use worktrunk::shell_exec::Cmd;
+
+use crate::warnings::Warnings;
+
+/// Lifecycle points `w` runs hooks at, in the order `w hooks list` shows them.
+pub(crate) const USER_HOOKS: [HookType; 4] = [
+    HookType::PostCreate,
+    HookType::PostSwitch,
+    HookType::PreRemove,
+    HookType::PostRemove,
+];
+
+/// Config key (and `W_HOOK` value) for a hook point, e.g. `post_create`.
+pub(crate) fn hook_key(hook: HookType) -> &'static str {
+    match hook {
+        HookType::PostCreate => "post_create",
+        HookType::PostStart => "post_start",
+        HookType::PostSwitch => "post_switch",
+        HookType::PreCommit => "pre_commit",
+        HookType::PreMerge => "pre_merge",
+        HookType::PostMerge => "post_merge",
+        HookType::PreRemove => "pre_remove",
+        HookType::PostRemove => "post_remove",
+    }
+}
+
+/// `[hooks]` in `~/.config/w/config.toml` or a project's `.w.toml`.
+///
+/// Each hook point maps to shell commands run in order.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct HooksConfig {
+    /// After `w new`/`w run`/`w cd` creates a worktree (inside it).
+    #[serde(default)]
+    pub(crate) post_create: Vec<String>,
+    /// After `w cd`/`w switch`/`w new` resolves an existing worktree (inside it).
+    #[serde(default)]
+    pub(crate) post_switch: Vec<String>,
+    /// Before `w rm` removes a worktree (inside it); failing aborts the removal under `abort`.
+    #[serde(default)]
+    pub(crate) pre_remove: Vec<String>,
+    /// After `w rm` removes a worktree (in the repository root).
+    #[serde(default)]
+    pub(crate) post_remove: Vec<String>,
+    /// What to do when a hook exits non-zero (unset: the other config's, else `abort`).
+    pub(crate) on_failure: Option<HookFailurePolicy>,
+}
+
+impl HooksConfig {
+    fn into_commands(self) -> [(HookType, Vec<String>); 4] {
+        [
+            (HookType::PostCreate, self.post_create),
+            (HookType::PostSwitch, self.post_switch),
+            (HookType::PreRemove, self.pre_remove),
+            (HookType::PostRemove, self.post_remove),
+        ]
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum HookFailurePolicy {
+    /// Stop at the first failing hook and exit non-zero (the worktree is kept).
+    #[default]
+    Abort,
+    /// Warn as each hook fails, run the rest, and summarize failures at the end.
+    Warn,
+    /// Run the rest silently and summarize failures at the end.
+    Continue,
+}
+
+impl HookFailurePolicy {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            HookFailurePolicy::Abort => "abort",
+            HookFailurePolicy::Warn => "warn",
+            HookFailurePolicy::Continue => "continue",
+        }
+    }
+}
+
+/// Which config file a hook setting came from.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct HookSource {
+    pub(crate) kind: HookSourceKind,
+    pub(crate) path: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum HookSourceKind {
+    Global,
+    Project,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct HookCommand {
+    pub(crate) command: String,
+    pub(crate) source: HookSource,
+    /// `false` for a project hook whose directory isn't in the global `trusted_projects`;
+    /// it's listed but never run.
+    pub(crate) trusted: bool,
+}
+
+/// Hooks in effect after layering the nearest `.w.toml` over the global config.
+///
+/// Each hook point lists global commands first, then project ones.
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct ResolvedHooks {
+    pub(crate) post_create: Vec<HookCommand>,
+    pub(crate) post_switch: Vec<HookCommand>,
+    pub(crate) pre_remove: Vec<HookCommand>,
+    pub(crate) post_remove: Vec<HookCommand>,
+    pub(crate) on_failure: HookFailurePolicy,
+    /// `None` when neither config sets `on_failure` (the default applies).
+    pub(crate) on_failure_source: Option<HookSource>,
+}
+
+impl ResolvedHooks {
+    pub(crate) fn commands(&self, hook: HookType) -> &[HookCommand] {
+        match hook {
+            HookType::PostCreate => &self.post_create,
+            HookType::PostSwitch => &self.post_switch,
+            HookType::PreRemove => &self.pre_remove,
+            HookType::PostRemove => &self.post_remove,
+            _ => &[],
+        }
+    }
+
+    fn commands_mut(&mut self, hook: HookType) -> &mut Vec<HookCommand> {
+        match hook {
+            HookType::PostCreate => &mut self.post_create,
+            HookType::PostSwitch => &mut self.post_switch,
+            HookType::PreRemove => &mut self.pre_remove,
+            HookType::PostRemove => &mut self.post_remove,
+            other => unreachable!("w has no {other} hooks"),
+        }
+    }
+}
+
+/// Resolve hooks from the global config (`config_path`, else the default one) and the
+/// `.w.toml` nearest to `start`.
+///
+/// Commands from both files run (global first); a project `on_failure` overrides the
+/// global one. A `.w.toml` is committed to the repository, so its hooks only run once
+/// its directory is listed in the global `trusted_projects`; until then they're resolved
+/// as untrusted and its `on_failure` is ignored.
+pub(crate) fn resolve_hooks(
+    start: &Path,
+    config_path: Option<&Path>,
+) -> anyhow::Result<ResolvedHooks> {
+    let mut layers = Vec::new();
+    let mut trusted_projects = Vec::new();
+    let global_path = match config_path {
+        Some(path) => Some(path.to_path_buf()),
+        None => Some(crate::repo::default_config_path()?).filter(|path| path.exists()),
+    };
+    if let Some(global_path) = global_path {
+        let config = crate::repo::load_config(&global_path)?;
+        trusted_projects = config.trusted_projects;
+        layers.push((HookSourceKind::Global, global_path, config.hooks, true));
+    }
+    if let Some(project_path) = crate::repo::find_project_config(start) {
+        let hooks = crate::repo::load_project_config(&project_path)?.hooks;
+        let trusted = is_trusted_project(&project_path, &trusted_projects);
+        layers.push((HookSourceKind::Project, project_path, hooks, trusted));
+    }
+
+    let mut resolved = ResolvedHooks::default();
+    for (kind, path, hooks, trusted) in layers {
+        let source = HookSource { kind, path };
+        if let Some(policy) = hooks.on_failure
+            && trusted
+        {
+            resolved.on_failure = policy;
+            resolved.on_failure_source = Some(source.clone());
+        }
+        for (hook, commands) in hooks.into_commands() {
+            resolved
+                .commands_mut(hook)
+                .extend(commands.into_iter().map(|command| HookCommand {
+                    command,
+                    source: source.clone(),
+                    trusted,
+                }));
+        }
+    }
+    Ok(resolved)
+}
+
+/// Whether the directory holding `project_path` is one of `trusted_projects`.
+fn is_trusted_project(project_path: &Path, trusted_projects: &[PathBuf]) -> bool {
+    let Some(dir) = project_path.parent() else {
+        return false;
+    };
+    let dir = dunce::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    trusted_projects
+        .iter()
+        .any(|trusted| dunce::canonicalize(trusted).is_ok_and(|trusted| trusted == dir))
+}
+
+/// Worktree a hook runs against; exported to hook commands as `W_*` env vars.
+pub(crate) struct HookContext<'a> {
+    pub(crate) repo_path: &'a Path,
+    pub(crate) worktree_path: &'a Path,
+    pub(crate) branch: &'a str,
+}
+
+/// Run `hook`'s commands in config order, applying `on_failure`.
+///
+/// Commands run inside the worktree, or in the repository root once it's gone
+/// (`post_remove`). Hook stdout is sent to stderr so stdout stays reserved for the path
+/// the shell wrapper reads. Skipped and failed hooks are reported through `warnings`.
+pub(crate) fn run_hooks(
+    hooks: &ResolvedHooks,
+    hook: HookType,
+    ctx: &HookContext<'_>,
+    warnings: &mut Warnings,
+) -> anyhow::Result<()> {
+    let key = hook_key(hook);
+    let commands = hooks.commands(hook);
+    let working_dir = if ctx.worktree_path.is_dir() {
+        ctx.worktree_path
+    } else {
+        ctx.repo_path
+    };
+
+    let mut failures = Vec::new();
+    let mut ran = 0;
+    for HookCommand {
+        command,
+        source,
+        trusted,
+    } in commands
+    {
+        if !trusted {
+            warnings.warn(format!(
+                "skipping untrusted {key} hook from {}: {command} (add its directory to `trusted_projects` in the global config to run it)",
+                source.path.display()
+            ));
+            continue;
+        }
+        ran += 1;
+        let result = Cmd::shell(command)
+            .current_dir(working_dir)
+            .context(ctx.branch)
+            .env("W_HOOK", key)
+            .env("W_BRANCH", ctx.branch)
+            .env("W_WORKTREE_PATH", ctx.worktree_path.to_string_lossy())
+            .env("W_REPO_PATH", ctx.repo_path.to_string_lossy())
+            .stdout(std::process::Stdio::from(std::io::stderr()))
+            .stream();
+        let Err(err) = result else {
+            continue;
+        };
+
+        match hooks.on_failure {
+            HookFailurePolicy::Abort => {
+                return Err(err).with_context(|| format!("{key} hook failed: {command}"));
+            }
+            HookFailurePolicy::Warn => {
+                warnings.warn(format!("{key} hook failed: {command}: {err:#}"));
+            }
+            HookFailurePolicy::Continue => {}
+        }
+        failures.push((command, err));
+    }
+
+    if !failures.is_empty() {
+        let mut summary = format!("{} of {ran} {key} hooks failed:", failures.len());
+        for (command, err) in &failures {
+            summary.push_str(&format!("\n  {command}: {err:#}"));
+        }
+        warnings.warn(summary);
+    }
+    Ok(())
+}
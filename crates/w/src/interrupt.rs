@@ -0,0 +1,31 @@
+use signal_hook::consts::SIGINT;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// Exit status after an interrupted scan (128 + SIGINT, as shells report a Ctrl-C).
+pub(crate) const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+static INTERRUPTED: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+/// Catch Ctrl-C (SIGINT on Unix, the console Ctrl+C event on Windows) for the rest of
+/// the process, so a scan can stop dispatching work and flush what it collected.
+///
+/// A second Ctrl-C exits immediately with [`INTERRUPTED_EXIT_CODE`].
+pub(crate) fn install() -> anyhow::Result<()> {
+    let flag = INTERRUPTED.get_or_init(|| Arc::new(AtomicBool::new(false)));
+    // Registered first, so it sees the flag before this signal sets it.
+    signal_hook::flag::register_conditional_shutdown(
+        SIGINT,
+        INTERRUPTED_EXIT_CODE,
+        Arc::clone(flag),
+    )?;
+    signal_hook::flag::register(SIGINT, Arc::clone(flag))?;
+    Ok(())
+}
+
+/// Whether Ctrl-C has been pressed since [`install`] (always `false` before it).
+pub(crate) fn requested() -> bool {
+    INTERRUPTED
+        .get()
+        .is_some_and(|flag| flag.load(Ordering::Relaxed))
+}
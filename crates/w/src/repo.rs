@@ -1,8 +1,11 @@
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, UNIX_EPOCH};
 use worktrunk::git::Repository;
 
 #[derive(Debug, Deserialize)]
@@ -13,8 +16,25 @@ pub(crate) struct WConfig {
     pub(crate) max_depth: usize,
     #[serde(default = "default_max_concurrent_repos")]
     pub(crate) max_concurrent_repos: usize,
+    /// Budget for concurrent git subprocesses across all repo workers (0 = unlimited).
+    pub(crate) max_concurrent_commands: Option<usize>,
     #[serde(default)]
     pub(crate) ls: LsConfig,
+    #[serde(default)]
+    pub(crate) alias: BTreeMap<String, String>,
+    #[serde(default)]
+    pub(crate) picker: PickerConfig,
+    #[serde(default)]
+    pub(crate) hooks: crate::hooks::HooksConfig,
+    /// Directories whose `.w.toml` `[hooks]` may run.
+    #[serde(default)]
+    pub(crate) trusted_projects: Vec<PathBuf>,
+    #[serde(default)]
+    pub(crate) include_submodules: bool,
+    #[serde(default)]
+    pub(crate) project_identifier_format: ProjectIdentifierFormat,
+    /// Truncate branch names wider than this in text output and pickers.
+    pub(crate) max_branch_display_width: Option<usize>,
 }
 
 fn default_max_depth() -> usize {
@@ -28,27 +48,225 @@ fn default_max_concurrent_repos() -> usize {
 #[derive(Debug, Default, Deserialize)]
 pub(crate) struct LsConfig {
     pub(crate) preset: Option<crate::LsTextPreset>,
-    pub(crate) sort: Option<crate::LsSort>,
+    /// Comma-separated sort keys, same as `w ls --sort`.
+    pub(crate) sort: Option<String>,
+}
+
+/// Interactive picker (`skim`) settings; unset fields keep the built-in defaults.
+#[derive(Debug, Default, Deserialize)]
+#[cfg_attr(windows, allow(dead_code))]
+pub(crate) struct PickerConfig {
+    pub(crate) height: Option<String>,
+    pub(crate) prompt: Option<String>,
+    #[serde(default)]
+    pub(crate) reverse: bool,
+    /// Shell command for the preview window; `{path}` expands to the (quoted) selected path.
+    pub(crate) preview_command: Option<String>,
+    /// Preview the highlighted worktree's branch, ahead/behind, and changed files
+    /// (`w __preview`); `preview_command` takes precedence when both are set.
+    #[serde(default)]
+    pub(crate) preview: bool,
+    /// Preview window layout, e.g. `right:50%` or `down:10`.
+    pub(crate) preview_window: Option<String>,
+    /// Make `w switch` pick a repository before its worktrees, as with `--two-stage`.
+    #[serde(default)]
+    pub(crate) two_stage: bool,
+    /// The top-level `project_identifier_format`, filled in by `load_picker_config`.
+    #[serde(skip)]
+    pub(crate) project_identifier_format: ProjectIdentifierFormat,
+    /// The top-level `max_branch_display_width`, filled in by `load_picker_config`.
+    #[serde(skip)]
+    pub(crate) max_branch_display_width: Option<usize>,
+}
+
+/// Display template for project identifiers (`project_identifier_format` in config).
+///
+/// Expands `{host}`, `{owner}`, and `{repo}` from the canonical `host/owner/repo`
+/// identifier. Only text output and pickers use it; the index, JSON/TSV/CSV output, and
+/// `--filter` matching keep the canonical identifier.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub(crate) struct ProjectIdentifierFormat(String);
+
+const DEFAULT_PROJECT_IDENTIFIER_FORMAT: &str = "{host}/{owner}/{repo}";
+
+impl Default for ProjectIdentifierFormat {
+    fn default() -> Self {
+        Self(DEFAULT_PROJECT_IDENTIFIER_FORMAT.to_string())
+    }
+}
+
+impl TryFrom<String> for ProjectIdentifierFormat {
+    type Error = anyhow::Error;
+
+    fn try_from(template: String) -> anyhow::Result<Self> {
+        let mut rest = template.as_str();
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..].find('}').with_context(|| {
+                format!("unclosed `{{` in project_identifier_format: {template}")
+            })?;
+            let placeholder = &rest[start + 1..start + end];
+            if !matches!(placeholder, "host" | "owner" | "repo") {
+                anyhow::bail!(
+                    "unknown placeholder {{{placeholder}}} in project_identifier_format \
+                     (expected {{host}}, {{owner}}, or {{repo}})"
+                );
+            }
+            rest = &rest[start + end + 1..];
+        }
+        Ok(Self(template))
+    }
+}
+
+impl ProjectIdentifierFormat {
+    /// Format a canonical identifier for display.
+    ///
+    /// Identifiers that aren't `host/owner/repo` (e.g. the repo path used when there's
+    /// no remote) are shown unchanged. Nested groups (`host/group/sub/repo`) make up
+    /// `{owner}`.
+    pub(crate) fn apply<'a>(&self, identifier: &'a str) -> Cow<'a, str> {
+        if self.0 == DEFAULT_PROJECT_IDENTIFIER_FORMAT || Path::new(identifier).is_absolute() {
+            return Cow::Borrowed(identifier);
+        }
+        let Some((host, rest)) = identifier.split_once('/') else {
+            return Cow::Borrowed(identifier);
+        };
+        let Some((owner, repo)) = rest.rsplit_once('/') else {
+            return Cow::Borrowed(identifier);
+        };
+        Cow::Owned(
+            self.0
+                .replace("{host}", host)
+                .replace("{owner}", owner)
+                .replace("{repo}", repo),
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct RepoIndex {
     pub(crate) schema_version: u32,
     pub(crate) repos: Vec<RepoEntry>,
+    /// `false` while a scan is in progress (or was interrupted); the next scan resumes it.
+    #[serde(default = "default_index_complete")]
+    pub(crate) complete: bool,
+    /// Progress of an incomplete scan, keyed by subtree path.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) scanned: BTreeMap<String, ScannedSubtree>,
+}
+
+/// Caches written before scans were resumable are always complete.
+fn default_index_complete() -> bool {
+    true
+}
+
+/// A scanned subtree (a root, or a directory directly under one) in a partial index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ScannedSubtree {
+    /// Directory mtime when scanned; the subtree is rescanned if it changed.
+    pub(crate) mtime_ns: Option<u64>,
+    /// Repo paths found under the subtree.
+    pub(crate) repos: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct RepoEntry {
     pub(crate) path: String,
     pub(crate) project_identifier: String,
+    /// Path of the superproject when this entry is a submodule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) parent: Option<String>,
+}
+
+const W_CONFIG_DIR_ENV: &str = "W_CONFIG_DIR";
+
+/// The global `--config-dir`, set once at startup.
+static CONFIG_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Use `dir` for `w`'s config and cache for the rest of the process (`--config-dir`).
+///
+/// Only the first call takes effect.
+pub(crate) fn set_config_dir(dir: PathBuf) {
+    let dir = std::path::absolute(&dir).unwrap_or(dir);
+    let _ = CONFIG_DIR.set(dir);
+}
+
+/// A single directory holding both `config.toml` and `cache/`, in place of the
+/// platform's config and cache directories: `--config-dir`, else `$W_CONFIG_DIR`.
+fn config_dir_override() -> Option<PathBuf> {
+    if let Some(dir) = CONFIG_DIR.get() {
+        return Some(dir.clone());
+    }
+    std::env::var_os(W_CONFIG_DIR_ENV)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
 }
 
 pub(crate) fn default_config_path() -> anyhow::Result<PathBuf> {
+    if let Some(dir) = config_dir_override() {
+        return Ok(dir.join("config.toml"));
+    }
     Ok(xdg_config_dir()?.join("w").join("config.toml"))
 }
 
+/// `w`'s cache directory (`$XDG_CACHE_HOME/w`, or `~/.cache/w`; `<config-dir>/cache`
+/// with `--config-dir`/`$W_CONFIG_DIR`).
+pub(crate) fn w_cache_dir() -> anyhow::Result<PathBuf> {
+    if let Some(dir) = config_dir_override() {
+        return Ok(dir.join("cache"));
+    }
+    Ok(xdg_cache_dir()?.join("w"))
+}
+
+/// Where to look for a `.w.toml` from, when set at startup (`-C`).
+static PROJECT_START_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Look for `.w.toml` from `dir` instead of the current directory for the rest of the
+/// process, as `-C` points `w` at another repo.
+///
+/// Only the first call takes effect.
+pub(crate) fn set_project_start_dir(dir: PathBuf) {
+    let dir = std::path::absolute(&dir).unwrap_or(dir);
+    let _ = PROJECT_START_DIR.set(dir);
+}
+
+/// The directory `.w.toml` discovery starts from: the `-C` directory, else the current one.
+fn project_start_dir() -> Option<PathBuf> {
+    match PROJECT_START_DIR.get() {
+        Some(dir) => Some(dir.clone()),
+        None => std::env::current_dir().ok(),
+    }
+}
+
 pub(crate) fn default_cache_path() -> anyhow::Result<PathBuf> {
-    Ok(xdg_cache_dir()?.join("w").join("repo-index.json"))
+    let cache_dir = w_cache_dir()?;
+    // A `.w.toml` scopes discovery to its project, so keep that index separate.
+    if let Some(start) = project_start_dir()
+        && let Some(project_config) = find_project_config(&start)
+        && let Some(project_dir) = project_config.parent()
+    {
+        return Ok(cache_dir
+            .join("projects")
+            .join(project_cache_key(project_dir))
+            .join("repo-index.json"));
+    }
+    Ok(cache_dir.join("repo-index.json"))
+}
+
+fn project_cache_key(project_dir: &Path) -> String {
+    canonicalize_best_effort(project_dir)
+        .to_string_lossy()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim_matches('_')
+        .to_string()
 }
 
 pub(crate) fn load_config(config_path: &Path) -> anyhow::Result<WConfig> {
@@ -61,48 +279,270 @@ pub(crate) fn load_config(config_path: &Path) -> anyhow::Result<WConfig> {
         .into_iter()
         .map(|root| expand_tilde(&root))
         .collect::<anyhow::Result<Vec<_>>>()?;
+    config.trusted_projects = config
+        .trusted_projects
+        .into_iter()
+        .map(|dir| expand_tilde(&dir))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(config)
+}
+
+pub(crate) const PROJECT_CONFIG_FILE: &str = ".w.toml";
+
+/// Repo discovery overrides from a project-local `.w.toml`; unset fields fall back to
+/// the global config.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct ProjectConfig {
+    pub(crate) repo_roots: Option<Vec<PathBuf>>,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) include_submodules: Option<bool>,
+    /// Project hooks, layered over the global `[hooks]`.
+    #[serde(default)]
+    pub(crate) hooks: crate::hooks::HooksConfig,
+}
+
+/// Find the nearest `.w.toml` in `start` or one of its ancestors.
+pub(crate) fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+/// Load a `.w.toml`, resolving relative `repo_roots` against the file's directory.
+pub(crate) fn load_project_config(config_path: &Path) -> anyhow::Result<ProjectConfig> {
+    let content = std::fs::read_to_string(config_path)
+        .with_context(|| format!("failed to read config file: {}", config_path.display()))?;
+    let mut config: ProjectConfig = toml::from_str(&content)
+        .with_context(|| format!("failed to parse TOML: {}", config_path.display()))?;
+    let base = config_path.parent().unwrap_or(Path::new("."));
+    if let Some(roots) = config.repo_roots.take() {
+        config.repo_roots = Some(
+            roots
+                .into_iter()
+                .map(|root| Ok(base.join(expand_tilde(&root)?)))
+                .collect::<anyhow::Result<Vec<_>>>()?,
+        );
+    }
     Ok(config)
 }
 
-pub(crate) fn build_repo_index(roots: &[PathBuf], max_depth: usize) -> anyhow::Result<RepoIndex> {
-    let mut candidates = Vec::new();
+/// Load the `.w.toml` nearest to the current directory (or the `-C` one), if any.
+pub(crate) fn discover_project_config() -> anyhow::Result<Option<(PathBuf, ProjectConfig)>> {
+    let Some(start) = project_start_dir() else {
+        return Ok(None);
+    };
+    let Some(config_path) = find_project_config(&start) else {
+        return Ok(None);
+    };
+    let config = load_project_config(&config_path)?;
+    Ok(Some((config_path, config)))
+}
+
+/// How often a running scan flushes its partial index to the cache.
+const INDEX_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How deep (and into what) `build_repo_index` looks.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ScanOptions {
+    pub(crate) max_depth: usize,
+    /// Also index initialized submodules of each repo found, with `parent` set.
+    pub(crate) include_submodules: bool,
+}
+
+/// Scan `roots` for repositories and write the index to `cache_path`.
+///
+/// Progress is flushed to the cache (marked `complete: false`) as subtrees finish, so an
+/// interrupted scan leaves a usable partial index. With `resume`, when the existing cache
+/// is partial, subtrees where no directory the scan looks at has changed mtime are reused
+/// instead of rescanned.
+pub(crate) fn build_repo_index(
+    roots: &[PathBuf],
+    options: ScanOptions,
+    cache_path: &Path,
+    resume: bool,
+) -> anyhow::Result<RepoIndex> {
+    let max_depth = options.max_depth;
+    let previous = if resume {
+        read_repo_index_cache(cache_path)
+            .ok()
+            .filter(|index| !index.complete)
+    } else {
+        None
+    };
+    let previous_entries = previous
+        .as_ref()
+        .map(|index| {
+            index
+                .repos
+                .iter()
+                .map(|entry| (entry.path.as_str(), entry))
+                .collect::<HashMap<_, _>>()
+        })
+        .unwrap_or_default();
+
+    let mut index = RepoIndex {
+        schema_version: 1,
+        repos: Vec::new(),
+        complete: false,
+        scanned: BTreeMap::new(),
+    };
+    let mut seen = HashSet::<String>::new();
+    let mut last_flush = Instant::now();
+
     for root in roots {
         if !root.exists() {
             continue;
         }
-        discover_repo_roots(root, 0, max_depth, &mut candidates)?;
+        for (subtree, depth) in scan_subtrees(root, max_depth)? {
+            let key = canonicalize_best_effort(&subtree)
+                .to_string_lossy()
+                .to_string();
+            let mtime_ns = subtree_mtime_ns(&subtree, depth, max_depth)?;
+            let reusable = previous
+                .as_ref()
+                .and_then(|previous| previous.scanned.get(&key))
+                .filter(|scanned| scanned.mtime_ns.is_some() && scanned.mtime_ns == mtime_ns);
+
+            let mut parents = HashMap::new();
+            let repo_paths = match reusable {
+                Some(scanned) => scanned.repos.clone(),
+                None => {
+                    let mut candidates = Vec::new();
+                    discover_repo_roots(&subtree, depth, max_depth, &mut candidates)?;
+                    let mut paths = candidates
+                        .iter()
+                        .map(|path| canonicalize_best_effort(path).to_string_lossy().to_string())
+                        .collect::<Vec<_>>();
+                    if options.include_submodules {
+                        let mut pending = paths.clone();
+                        while let Some(parent) = pending.pop() {
+                            for submodule in submodule_paths(Path::new(&parent)) {
+                                let submodule = canonicalize_best_effort(&submodule)
+                                    .to_string_lossy()
+                                    .to_string();
+                                parents.insert(submodule.clone(), parent.clone());
+                                pending.push(submodule.clone());
+                                paths.push(submodule);
+                            }
+                        }
+                    }
+                    paths
+                }
+            };
+
+            for path in &repo_paths {
+                if !seen.insert(path.clone()) {
+                    continue;
+                }
+                let entry = match previous_entries.get(path.as_str()) {
+                    Some(&entry) => Some(entry.clone()),
+                    None => identify_repo(path, parents.get(path).cloned()),
+                };
+                index.repos.extend(entry);
+            }
+            index.scanned.insert(
+                key,
+                ScannedSubtree {
+                    mtime_ns,
+                    repos: repo_paths,
+                },
+            );
+
+            if last_flush.elapsed() >= INDEX_FLUSH_INTERVAL {
+                index.repos.sort_by(|a, b| a.path.cmp(&b.path));
+                write_repo_index_cache(cache_path, &index)?;
+                last_flush = Instant::now();
+            }
+        }
     }
 
-    let mut seen = HashSet::<String>::new();
-    let mut repos = Vec::new();
+    index.repos.sort_by(|a, b| a.path.cmp(&b.path));
+    index.complete = true;
+    index.scanned.clear();
+    write_repo_index_cache(cache_path, &index)?;
 
-    for path in candidates {
-        let path = canonicalize_best_effort(&path);
-        let path_str = path.to_string_lossy().to_string();
-        if !seen.insert(path_str.clone()) {
-            continue;
-        }
+    Ok(index)
+}
 
-        let repo = match Repository::at(&path) {
-            Ok(repo) => repo,
-            Err(_) => continue,
-        };
-        let project_identifier = repo
-            .project_identifier()
-            .unwrap_or_else(|_| path_str.clone());
-
-        repos.push(RepoEntry {
-            path: path_str,
-            project_identifier,
-        });
+fn identify_repo(path: &str, parent: Option<String>) -> Option<RepoEntry> {
+    let repo = Repository::at(path).ok()?;
+    let project_identifier = repo
+        .project_identifier()
+        .unwrap_or_else(|_| path.to_string());
+    Some(RepoEntry {
+        path: path.to_string(),
+        project_identifier,
+        parent,
+    })
+}
+
+/// Checked-out submodules directly under `repo_dir` (from `git submodule status`).
+///
+/// Uninitialized submodules have no repository to index and are skipped; any git
+/// failure yields no submodules rather than failing the scan.
+fn submodule_paths(repo_dir: &Path) -> Vec<PathBuf> {
+    let Ok(repo) = Repository::at(repo_dir) else {
+        return Vec::new();
+    };
+    let Ok(output) = repo.run_command(&["submodule", "status"]) else {
+        return Vec::new();
+    };
+    parse_submodule_status(&output)
+        .into_iter()
+        .map(|path| repo_dir.join(path))
+        .filter(|path| path.join(".git").exists())
+        .collect()
+}
+
+/// Paths of initialized submodules in `git submodule status` output.
+///
+/// Each line is `<state><sha> <path>[ (<describe>)]`, where state `-` means uninitialized.
+fn parse_submodule_status(output: &str) -> Vec<&str> {
+    output
+        .lines()
+        .filter(|line| !line.starts_with('-'))
+        .filter_map(|line| line.get(1..)?.split_once(' '))
+        .map(|(_sha, rest)| rest.rsplit_once(" (").map_or(rest, |(path, _)| path))
+        .filter(|path| !path.is_empty())
+        .collect()
+}
+
+/// Units of scan progress: the root itself if it's a repo, otherwise its child directories.
+fn scan_subtrees(root: &Path, max_depth: usize) -> anyhow::Result<Vec<(PathBuf, usize)>> {
+    if is_git_repo_root(root) {
+        return Ok(vec![(root.to_path_buf(), 0)]);
     }
+    if max_depth == 0 {
+        return Ok(Vec::new());
+    }
+    Ok(child_dirs(root)?.into_iter().map(|dir| (dir, 1)).collect())
+}
 
-    repos.sort_by(|a, b| a.path.cmp(&b.path));
+/// Newest mtime among the directories `discover_repo_roots` looks at under `dir`.
+///
+/// A repo appearing or disappearing anywhere down to `max_depth` touches one of them,
+/// not just `dir` itself. `None` when any of them has no readable mtime.
+fn subtree_mtime_ns(dir: &Path, depth: usize, max_depth: usize) -> anyhow::Result<Option<u64>> {
+    let Some(mut newest) = dir_mtime_ns(dir) else {
+        return Ok(None);
+    };
+    if is_git_repo_root(dir) || depth >= max_depth {
+        return Ok(Some(newest));
+    }
+    for child in child_dirs(dir)? {
+        match subtree_mtime_ns(&child, depth + 1, max_depth)? {
+            Some(mtime) => newest = newest.max(mtime),
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(newest))
+}
 
-    Ok(RepoIndex {
-        schema_version: 1,
-        repos,
-    })
+fn dir_mtime_ns(dir: &Path) -> Option<u64> {
+    let modified = dir.metadata().and_then(|m| m.modified()).ok()?;
+    let since_epoch = modified.duration_since(UNIX_EPOCH).ok()?;
+    u64::try_from(since_epoch.as_nanos()).ok()
 }
 
 pub(crate) fn read_repo_index_cache(cache_path: &Path) -> anyhow::Result<RepoIndex> {
@@ -114,30 +554,58 @@ pub(crate) fn read_repo_index_cache(cache_path: &Path) -> anyhow::Result<RepoInd
 }
 
 pub(crate) fn write_repo_index_cache(cache_path: &Path, index: &RepoIndex) -> anyhow::Result<()> {
-    let Some(parent) = cache_path.parent() else {
-        anyhow::bail!(
-            "cache path has no parent directory: {}",
-            cache_path.display()
-        );
+    write_json_atomically(cache_path, index, "cache file")
+}
+
+/// Write `value` as pretty JSON to `path` (creating its directory), replacing the file
+/// in one rename so readers never see it half-written.
+///
+/// The JSON goes to a uniquely named temporary file next to `path` first, so
+/// concurrent writers never share (and clobber) a staging file; the last rename wins.
+/// `what` names the file in errors, e.g. `cache file`.
+pub(crate) fn write_json_atomically(
+    path: &Path,
+    value: &impl Serialize,
+    what: &str,
+) -> anyhow::Result<()> {
+    let Some(parent) = path.parent() else {
+        anyhow::bail!("{what} path has no parent directory: {}", path.display());
     };
     std::fs::create_dir_all(parent)
         .with_context(|| format!("failed to create cache dir: {}", parent.display()))?;
 
-    let tmp_path = cache_path.with_extension("tmp");
-    let json = serde_json::to_string_pretty(index).context("failed to serialize cache JSON")?;
-    std::fs::write(&tmp_path, json)
-        .with_context(|| format!("failed to write {}", tmp_path.display()))?;
-
-    std::fs::rename(&tmp_path, cache_path).with_context(|| {
-        format!(
-            "failed to atomically replace cache file at {}",
-            cache_path.display()
-        )
-    })?;
+    let json = serde_json::to_string_pretty(value)
+        .with_context(|| format!("failed to serialize {what}"))?;
+    let mut tmp = tempfile::NamedTempFile::new_in(parent)
+        .with_context(|| format!("failed to create a temporary file in {}", parent.display()))?;
+    std::io::Write::write_all(&mut tmp, json.as_bytes())
+        .with_context(|| format!("failed to write {}", tmp.path().display()))?;
+    tmp.persist(path)
+        .map_err(|err| err.error)
+        .with_context(|| format!("failed to atomically replace {what} at {}", path.display()))?;
 
     Ok(())
 }
 
+/// Load picker settings from `--config`, or the default config file when it exists.
+pub(crate) fn load_picker_config(config_path: Option<&Path>) -> anyhow::Result<PickerConfig> {
+    let config = match config_path {
+        Some(config_path) => load_config(config_path)?,
+        None => {
+            let config_path = default_config_path()?;
+            if !config_path.exists() {
+                return Ok(PickerConfig::default());
+            }
+            load_config(&config_path)?
+        }
+    };
+    Ok(PickerConfig {
+        project_identifier_format: config.project_identifier_format,
+        max_branch_display_width: config.max_branch_display_width,
+        ..config.picker
+    })
+}
+
 pub(crate) fn select_repo_by_filter(index: &RepoIndex, filter: &str) -> Option<PathBuf> {
     let needle = filter.to_lowercase();
     index
@@ -150,15 +618,63 @@ pub(crate) fn select_repo_by_filter(index: &RepoIndex, filter: &str) -> Option<P
         .map(|repo| PathBuf::from(&repo.path))
 }
 
+/// Build skim options for a tab-separated picker whose `path_field` (1-based) holds the path.
+#[cfg(not(windows))]
+pub(crate) fn skim_options(
+    picker: &PickerConfig,
+    default_prompt: &str,
+    path_field: usize,
+) -> anyhow::Result<skim::prelude::SkimOptions> {
+    use skim::prelude::*;
+
+    let mut builder = SkimOptionsBuilder::default();
+    builder
+        .height(picker.height.clone().unwrap_or_else(|| "50%".into()))
+        .multi(false)
+        .prompt(
+            picker
+                .prompt
+                .clone()
+                .unwrap_or_else(|| default_prompt.into()),
+        )
+        .reverse(picker.reverse);
+
+    let preview = match &picker.preview_command {
+        Some(command) => Some(command.replace("{path}", &format!("{{{path_field}}}"))),
+        None if picker.preview => {
+            let exe = std::env::current_exe().context("failed to locate the running w binary")?;
+            Some(format!(
+                "{} __preview {{{path_field}}}",
+                crate::shell_quote(&exe.to_string_lossy())
+            ))
+        }
+        None => None,
+    };
+    if let Some(preview) = preview {
+        builder.delimiter("\t".into()).preview(Some(preview));
+        if let Some(window) = &picker.preview_window {
+            builder.preview_window(window.clone());
+        }
+    }
+
+    builder.build().context("failed to build skim options")
+}
+
 #[cfg(windows)]
-pub(crate) fn pick_repo_interactive(_index: &RepoIndex) -> anyhow::Result<Option<PathBuf>> {
+pub(crate) fn pick_repo_interactive(
+    _index: &RepoIndex,
+    _picker: &PickerConfig,
+) -> anyhow::Result<Option<PathBuf>> {
     anyhow::bail!(
         "interactive picker is not supported on Windows; pass --filter for non-interactive selection"
     );
 }
 
 #[cfg(not(windows))]
-pub(crate) fn pick_repo_interactive(index: &RepoIndex) -> anyhow::Result<Option<PathBuf>> {
+pub(crate) fn pick_repo_interactive(
+    index: &RepoIndex,
+    picker: &PickerConfig,
+) -> anyhow::Result<Option<PathBuf>> {
     use std::io::{Cursor, IsTerminal};
 
     if !std::io::stdin().is_terminal() {
@@ -169,17 +685,20 @@ pub(crate) fn pick_repo_interactive(index: &RepoIndex) -> anyhow::Result<Option<
 
     use skim::prelude::*;
 
-    let options = SkimOptionsBuilder::default()
-        .height("50%".into())
-        .multi(false)
-        .prompt("repo> ".into())
-        .build()
-        .context("failed to build skim options")?;
+    let options = skim_options(picker, "repo> ", 2)?;
 
     let input = index
         .repos
         .iter()
-        .map(|repo| format!("{}\t{}", repo.project_identifier, repo.path))
+        .map(|repo| {
+            format!(
+                "{}\t{}",
+                picker
+                    .project_identifier_format
+                    .apply(&repo.project_identifier),
+                repo.path
+            )
+        })
         .collect::<Vec<_>>()
         .join("\n");
 
@@ -214,9 +733,18 @@ fn discover_repo_roots(
         return Ok(());
     }
 
+    for child in child_dirs(dir)? {
+        discover_repo_roots(&child, depth + 1, max_depth, out)?;
+    }
+
+    Ok(())
+}
+
+/// Subdirectories of `dir` worth scanning, sorted by name (symlinks and ignored names skipped).
+fn child_dirs(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
     let read_dir = match std::fs::read_dir(dir) {
         Ok(read_dir) => read_dir,
-        Err(err) if err.kind() == std::io::ErrorKind::PermissionDenied => return Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::PermissionDenied => return Ok(Vec::new()),
         Err(err) => {
             return Err(err).with_context(|| format!("failed to read dir: {}", dir.display()));
         }
@@ -230,24 +758,16 @@ fn discover_repo_roots(
     })?;
     entries.sort_by_key(|entry| entry.file_name());
 
-    for entry in entries {
-        let file_type = match entry.file_type() {
-            Ok(ft) => ft,
-            Err(_) => continue,
-        };
-        if !file_type.is_dir() || file_type.is_symlink() {
-            continue;
-        }
-
-        let file_name = entry.file_name();
-        if is_ignored_dir_name(&file_name) {
-            continue;
-        }
-
-        discover_repo_roots(&entry.path(), depth + 1, max_depth, out)?;
-    }
-
-    Ok(())
+    Ok(entries
+        .into_iter()
+        .filter(|entry| {
+            entry
+                .file_type()
+                .is_ok_and(|ft| ft.is_dir() && !ft.is_symlink())
+        })
+        .filter(|entry| !is_ignored_dir_name(&entry.file_name()))
+        .map(|entry| entry.path())
+        .collect())
 }
 
 fn is_git_repo_root(dir: &Path) -> bool {
@@ -300,7 +820,7 @@ fn xdg_cache_dir() -> anyhow::Result<PathBuf> {
     Ok(home_dir()?.join(".cache"))
 }
 
-fn home_dir() -> anyhow::Result<PathBuf> {
+pub(crate) fn home_dir() -> anyhow::Result<PathBuf> {
     if let Ok(home) = std::env::var("HOME")
         && !home.trim().is_empty()
     {
@@ -317,7 +837,7 @@ fn home_dir() -> anyhow::Result<PathBuf> {
     anyhow::bail!("cannot determine home directory (set $HOME or $USERPROFILE)")
 }
 
-fn expand_tilde(path: &Path) -> anyhow::Result<PathBuf> {
+pub(crate) fn expand_tilde(path: &Path) -> anyhow::Result<PathBuf> {
     let path_str = path.to_string_lossy();
     let Some(rest) = path_str.strip_prefix('~') else {
         return Ok(path.to_path_buf());
@@ -0,0 +1,277 @@
+use anyhow::Context;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::process::Command;
+
+/// GitHub repository that publishes `w` release archives (see `.github/workflows/release.yml`).
+const RELEASE_REPO: &str = "prateek/w";
+
+/// Overrides where the latest-release JSON is fetched from (a mirror, or a `file://` URL in tests).
+const RELEASE_URL_ENV: &str = "W_SELF_UPDATE_RELEASE_URL";
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// `w self-update [--check]`: replace the running binary with the latest release.
+///
+/// Every network call happens here, only when the user asks for it. Downloads go through
+/// `curl`; the release lookup uses `gh api` when `gh` is installed (authenticated, so no
+/// anonymous rate limit) and falls back to `curl` against the GitHub API.
+pub(crate) fn self_update(check: bool) -> anyhow::Result<()> {
+    let exe = std::env::current_exe().context("failed to locate the running w binary")?;
+    let exe = dunce::canonicalize(&exe).unwrap_or(exe);
+    if !check && let Some(hint) = package_manager_hint(&exe) {
+        eprintln!(
+            "w self-update: {} is managed by {hint}; skipping",
+            exe.display()
+        );
+        return Ok(());
+    }
+
+    eprintln!("Checking the latest w release...");
+    let release = fetch_latest_release()?;
+    let current = semver::Version::parse(env!("CARGO_PKG_VERSION"))?;
+    let latest = semver::Version::parse(release.tag_name.trim_start_matches('v'))
+        .with_context(|| format!("unexpected release tag: {}", release.tag_name))?;
+    if latest <= current {
+        println!("w {current} is up to date");
+        return Ok(());
+    }
+    if check {
+        println!("update available: w {current} -> {latest}");
+        return Ok(());
+    }
+
+    let (platform, arch) = release_target()?;
+    let archive_name = format!("w-{}-{platform}-{arch}.tar.gz", release.tag_name);
+    let find_asset = |name: &str| {
+        release
+            .assets
+            .iter()
+            .find(|asset| asset.name == name)
+            .with_context(|| format!("release {} has no {name}", release.tag_name))
+    };
+    let archive = find_asset(&archive_name)?;
+    let checksum = find_asset(&format!("{archive_name}.sha256"))?;
+
+    // A fresh private directory (random name, mode 0700), so nobody else can plant or
+    // swap files between the checksum check and the install.
+    let work_dir = tempfile::Builder::new()
+        .prefix("w-self-update-")
+        .tempdir()
+        .context("failed to create a temporary directory")?;
+    download_and_install(work_dir.path(), archive, checksum, &exe)?;
+
+    println!("updated w {current} -> {latest} ({})", exe.display());
+    Ok(())
+}
+
+fn download_and_install(
+    work_dir: &Path,
+    archive: &ReleaseAsset,
+    checksum: &ReleaseAsset,
+    exe: &Path,
+) -> anyhow::Result<()> {
+    eprintln!("Downloading {}...", archive.name);
+    let archive_path = work_dir.join(&archive.name);
+    curl_download(&archive.browser_download_url, &archive_path)?;
+    let checksum_text = String::from_utf8(curl_get(&checksum.browser_download_url)?)
+        .context("checksum file is not UTF-8")?;
+
+    eprintln!("Verifying checksum...");
+    verify_sha256(&archive_path, &checksum_text)?;
+
+    let status = Command::new("tar")
+        .arg("-xzf")
+        .arg(&archive_path)
+        .arg("-C")
+        .arg(work_dir)
+        .status()
+        .context("failed to run tar")?;
+    anyhow::ensure!(status.success(), "tar failed to extract {}", archive.name);
+
+    let package_dir = archive
+        .name
+        .strip_suffix(".tar.gz")
+        .unwrap_or(&archive.name);
+    let binary_name = |name: &str| format!("{name}{}", std::env::consts::EXE_SUFFIX);
+    let new_binary = work_dir.join(package_dir).join(binary_name("w"));
+    anyhow::ensure!(
+        new_binary.is_file(),
+        "{} has no w binary at {package_dir}/",
+        archive.name
+    );
+
+    eprintln!("Replacing {}...", exe.display());
+    replace_binary(&new_binary, exe)?;
+
+    // The archive's `wt` goes with this `w`; update it too when it was installed alongside.
+    let new_wt = work_dir.join(package_dir).join(binary_name("wt"));
+    let wt = exe.with_file_name(binary_name("wt"));
+    if new_wt.is_file() && wt.is_file() {
+        eprintln!("Replacing {}...", wt.display());
+        replace_binary(&new_wt, &wt)?;
+    }
+    Ok(())
+}
+
+fn fetch_latest_release() -> anyhow::Result<Release> {
+    let body = match std::env::var(RELEASE_URL_ENV) {
+        Ok(url) => curl_get(&url)?,
+        Err(_) if tool_available("gh") => {
+            let output = Command::new("gh")
+                .args(["api", &format!("repos/{RELEASE_REPO}/releases/latest")])
+                .output()
+                .context("failed to run gh")?;
+            anyhow::ensure!(
+                output.status.success(),
+                "gh api failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            output.stdout
+        }
+        Err(_) => curl_get(&format!(
+            "https://api.github.com/repos/{RELEASE_REPO}/releases/latest"
+        ))?,
+    };
+    serde_json::from_slice(&body).context("failed to parse the latest release")
+}
+
+fn tool_available(program: &str) -> bool {
+    Command::new(program)
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+fn curl_get(url: &str) -> anyhow::Result<Vec<u8>> {
+    let output = Command::new("curl")
+        .args(["-fsSL", url])
+        .output()
+        .context("failed to run curl (needed to download releases)")?;
+    anyhow::ensure!(
+        output.status.success(),
+        "failed to fetch {url}: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(output.stdout)
+}
+
+fn curl_download(url: &str, dest: &Path) -> anyhow::Result<()> {
+    let status = Command::new("curl")
+        .args(["-fSL", "--progress-bar", "-o"])
+        .arg(dest)
+        .arg(url)
+        .status()
+        .context("failed to run curl (needed to download releases)")?;
+    anyhow::ensure!(status.success(), "failed to download {url}");
+    Ok(())
+}
+
+/// Check `path` against a `sha256sum`-style line (`<hex digest>  <file name>`).
+fn verify_sha256(path: &Path, checksum_text: &str) -> anyhow::Result<()> {
+    let expected = checksum_text
+        .split_whitespace()
+        .next()
+        .context("checksum file is empty")?
+        .to_ascii_lowercase();
+    let bytes =
+        std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let actual = Sha256::digest(&bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    anyhow::ensure!(
+        actual == expected,
+        "checksum mismatch for {} (expected {expected}, got {actual}); not installing",
+        path.display()
+    );
+    Ok(())
+}
+
+/// Release archive naming (`w-<tag>-<platform>-<arch>.tar.gz`) for this build.
+fn release_target() -> anyhow::Result<(&'static str, &'static str)> {
+    let platform = match std::env::consts::OS {
+        "linux" => "linux",
+        "macos" => "macos",
+        "windows" => "windows",
+        other => anyhow::bail!("no prebuilt w releases for {other}"),
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "X64",
+        "aarch64" => "ARM64",
+        other => anyhow::bail!("no prebuilt w releases for {other}"),
+    };
+    Ok((platform, arch))
+}
+
+/// Where `exe` was installed by a package manager, how to update it there instead.
+fn package_manager_hint(exe: &Path) -> Option<&'static str> {
+    let path = exe.to_string_lossy();
+    if path.contains("/Cellar/") || path.starts_with("/home/linuxbrew/") {
+        Some("Homebrew (run `brew upgrade w`)")
+    } else if path.starts_with("/nix/store/") {
+        Some("Nix (update it through your Nix configuration)")
+    } else if ["/usr/bin", "/bin", "/usr/sbin", "/sbin"]
+        .iter()
+        .any(|dir| exe.parent() == Some(Path::new(dir)))
+    {
+        Some("your system package manager")
+    } else {
+        None
+    }
+}
+
+/// Swap `new_binary` in for `exe`, staging it next to `exe` so the final rename stays on
+/// one filesystem.
+///
+/// The staged file gets a random name and is created exclusively (`create_new`), so it
+/// can't be a file or symlink someone put there in advance.
+fn replace_binary(new_binary: &Path, exe: &Path) -> anyhow::Result<()> {
+    let dir = exe.parent().context("binary has no parent directory")?;
+    let mut staged = tempfile::Builder::new()
+        .prefix(".w-self-update-")
+        .tempfile_in(dir)
+        .with_context(|| format!("failed to stage the new binary in {}", dir.display()))?;
+    let mut source = std::fs::File::open(new_binary)
+        .with_context(|| format!("failed to read {}", new_binary.display()))?;
+    std::io::copy(&mut source, staged.as_file_mut())
+        .with_context(|| format!("failed to write {}", staged.path().display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        staged
+            .as_file()
+            .set_permissions(std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    // Windows can't overwrite a running executable, but it can rename it out of the way.
+    #[cfg(windows)]
+    let old = {
+        let old = exe.with_extension("exe.old");
+        let _ = std::fs::remove_file(&old);
+        std::fs::rename(exe, &old)
+            .with_context(|| format!("failed to move {} aside", exe.display()))?;
+        old
+    };
+
+    // On failure the staged file is dropped, which deletes it.
+    if let Err(err) = staged.persist(exe) {
+        // Put the old binary back rather than leave nothing at the install path.
+        #[cfg(windows)]
+        let _ = std::fs::rename(&old, exe);
+        return Err(err.error).with_context(|| format!("failed to replace {}", exe.display()));
+    }
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use serde::Serialize;
+
+/// A non-fatal problem a command ran into (`warnings` in JSON output).
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Warning {
+    pub(crate) message: String,
+}
+
+/// Collects a command's warnings: each is printed to stderr as it happens (unless
+/// `--quiet`) and kept, so JSON output can list them alongside its `errors`.
+#[derive(Debug)]
+pub(crate) struct Warnings {
+    /// Prefix for stderr lines, e.g. `w ls`.
+    command: &'static str,
+    quiet: bool,
+    collected: Vec<Warning>,
+}
+
+impl Warnings {
+    pub(crate) fn new(command: &'static str, quiet: bool) -> Self {
+        Self {
+            command,
+            quiet,
+            collected: Vec::new(),
+        }
+    }
+
+    pub(crate) fn warn(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        if !self.quiet {
+            eprintln!("{}: warning: {message}", self.command);
+        }
+        self.collected.push(Warning { message });
+    }
+
+    /// Whether stderr diagnostics are suppressed (`--quiet`).
+    pub(crate) fn quiet(&self) -> bool {
+        self.quiet
+    }
+
+    /// Hand over the warnings collected so far, e.g. for one `--watch` frame.
+    pub(crate) fn take(&mut self) -> Vec<Warning> {
+        std::mem::take(&mut self.collected)
+    }
+}
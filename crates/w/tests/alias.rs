@@ -0,0 +1,45 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+
+fn write_config(config_home: &std::path::Path, content: &str) {
+    let dir = config_home.join("w");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("config.toml"), content).unwrap();
+}
+
+#[test]
+fn w_alias_expands_to_subcommand() {
+    let tmp = tempfile::tempdir().unwrap();
+    write_config(tmp.path(), "[alias]\nsh = \"shell init\"\n");
+
+    let output = cargo_bin_cmd!("w")
+        .env("XDG_CONFIG_HOME", tmp.path())
+        .args(["sh", "zsh"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w sh failed: {output:?}");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("eval \"$(w shell init zsh)\""));
+}
+
+#[test]
+fn w_alias_rejects_recursion() {
+    let tmp = tempfile::tempdir().unwrap();
+    write_config(tmp.path(), "[alias]\na = \"b\"\nb = \"a --print\"\n");
+
+    let output = cargo_bin_cmd!("w")
+        .env("XDG_CONFIG_HOME", tmp.path())
+        .args(["a"])
+        .output()
+        .unwrap();
+
+    assert!(
+        !output.status.success(),
+        "expected failure, got: {output:?}"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("recursive alias: a -> b -> a"),
+        "stderr did not mention recursion:\n{stderr}"
+    );
+}
@@ -84,3 +84,196 @@ fn w_cd_fails_for_missing_branch() {
         .unwrap();
     assert!(!output.status.success());
 }
+
+fn write_w_config(config_home: &Path, root: &Path) {
+    let config_dir = config_home.join("w");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        format!(
+            "repo_roots = [{:?}]\nmax_depth = 2\n",
+            root.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn w_cd_outside_repo_searches_indexed_repos() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path().join("root");
+    let repo_a = root.join("repo_a");
+    let repo_b = root.join("repo_b");
+    std::fs::create_dir_all(&repo_a).unwrap();
+    std::fs::create_dir_all(&repo_b).unwrap();
+    init_repo(&repo_a);
+    init_repo(&repo_b);
+
+    let wt_a = tmp.path().join("wt_a_only");
+    git(
+        &repo_a,
+        &["worktree", "add", "-b", "only-a", wt_a.to_str().unwrap()],
+    );
+    let wt_shared_a = tmp.path().join("wt_shared_a");
+    let wt_shared_b = tmp.path().join("wt_shared_b");
+    git(
+        &repo_a,
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "shared",
+            wt_shared_a.to_str().unwrap(),
+        ],
+    );
+    git(
+        &repo_b,
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "shared",
+            wt_shared_b.to_str().unwrap(),
+        ],
+    );
+
+    let outside = tmp.path().join("outside");
+    std::fs::create_dir_all(&outside).unwrap();
+    let config_home = tmp.path().join("config");
+    write_w_config(&config_home, &root);
+
+    let run_cd = |args: &[&str]| {
+        cargo_bin_cmd!("w")
+            .current_dir(&outside)
+            .env("GIT_CEILING_DIRECTORIES", tmp.path())
+            .env("XDG_CONFIG_HOME", &config_home)
+            .env("XDG_CACHE_HOME", tmp.path().join("cache"))
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    let output = run_cd(&["cd", "only-a"]);
+    assert!(output.status.success(), "w cd failed: {output:?}");
+    assert_eq!(
+        parse_path(&output.stdout),
+        dunce::canonicalize(&wt_a).unwrap()
+    );
+
+    // Ambiguous matches fall through to the picker, which needs a TTY.
+    let output = run_cd(&["cd", "shared"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    #[cfg(windows)]
+    assert!(stderr.contains("interactive picker is not supported on Windows"));
+    #[cfg(not(windows))]
+    assert!(
+        stderr.contains("interactive picker requires a TTY"),
+        "stderr: {stderr}"
+    );
+
+    let output = run_cd(&["cd", "missing"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no worktree for branch missing in any indexed repo"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn w_cd_any_repo_searches_index_from_inside_a_repo() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path().join("root");
+    let repo_a = root.join("repo_a");
+    let repo_b = root.join("repo_b");
+    std::fs::create_dir_all(&repo_a).unwrap();
+    std::fs::create_dir_all(&repo_b).unwrap();
+    init_repo(&repo_a);
+    init_repo(&repo_b);
+
+    let wt_b = tmp.path().join("wt_b_only");
+    git(
+        &repo_b,
+        &["worktree", "add", "-b", "only-b", wt_b.to_str().unwrap()],
+    );
+    let config_home = tmp.path().join("config");
+    write_w_config(&config_home, &root);
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(&repo_a)
+        .env("XDG_CONFIG_HOME", &config_home)
+        .env("XDG_CACHE_HOME", tmp.path().join("cache"))
+        .args(["cd", "--any-repo", "only-b"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "w cd --any-repo failed: {output:?}"
+    );
+    assert_eq!(
+        parse_path(&output.stdout),
+        dunce::canonicalize(&wt_b).unwrap()
+    );
+}
+
+#[test]
+fn w_cd_accepts_a_path_inside_a_worktree() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let repo = home.join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    let feature = home.join("repo.feature");
+    git(
+        &repo,
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "feature",
+            feature.to_str().unwrap(),
+        ],
+    );
+    std::fs::create_dir_all(feature.join("src/deep")).unwrap();
+    let outside = home.join("outside");
+    std::fs::create_dir_all(&outside).unwrap();
+
+    let cd = |arg: &str| {
+        cargo_bin_cmd!("w")
+            .current_dir(&repo)
+            .env("HOME", home)
+            .env("XDG_CONFIG_HOME", home.join(".config"))
+            .env("XDG_CACHE_HOME", home.join(".cache"))
+            .args(["cd", arg])
+            .output()
+            .unwrap()
+    };
+    let feature_root = dunce::canonicalize(&feature).unwrap();
+
+    for arg in [
+        "../repo.feature/src/deep",
+        "~/repo.feature/src",
+        feature.join("src").to_str().unwrap(),
+    ] {
+        let output = cd(arg);
+        assert!(output.status.success(), "w cd {arg} failed: {output:?}");
+        assert_eq!(parse_path(&output.stdout), feature_root, "w cd {arg}");
+    }
+    let output = cd("./");
+    assert!(output.status.success(), "w cd ./ failed: {output:?}");
+    assert_eq!(
+        parse_path(&output.stdout),
+        dunce::canonicalize(&repo).unwrap()
+    );
+
+    let output = cd(outside.to_str().unwrap());
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("is not inside a git worktree"), "{stderr}");
+
+    let output = cd("./missing");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("not a directory"), "{stderr}");
+}
@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+
+fn git(current_dir: &Path, args: &[&str]) {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(current_dir)
+        .output()
+        .unwrap_or_else(|e| panic!("failed to run git {args:?}: {e}"));
+
+    if !output.status.success() {
+        panic!(
+            "git {args:?} failed\nstdout:\n{}\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+}
+
+fn init_repo(repo_dir: &Path) {
+    git(repo_dir, &["init", "-b", "main"]);
+    git(repo_dir, &["config", "user.name", "Test User"]);
+    git(repo_dir, &["config", "user.email", "test@example.com"]);
+
+    std::fs::write(repo_dir.join("README.md"), "hello\n").unwrap();
+    git(repo_dir, &["add", "README.md"]);
+    git(repo_dir, &["commit", "-m", "initial"]);
+}
+
+#[test]
+fn w_complete_branches_lists_local_branches() {
+    let tmp = tempfile::tempdir().unwrap();
+    init_repo(tmp.path());
+    git(tmp.path(), &["branch", "feature/a"]);
+    git(tmp.path(), &["branch", "fix-b"]);
+
+    let output = cargo_bin_cmd!("w")
+        .args([
+            "__complete",
+            "branches",
+            "--repo",
+            tmp.path().to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w __complete failed: {output:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "feature/a\nfix-b\nmain\n"
+    );
+}
+
+#[test]
+fn w_complete_branches_outside_repo_prints_nothing() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(tmp.path())
+        .env("GIT_CEILING_DIRECTORIES", tmp.path())
+        .args(["__complete", "branches"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w __complete failed: {output:?}");
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn w_preview_shows_branch_and_changed_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    init_repo(tmp.path());
+    std::fs::write(tmp.path().join("README.md"), "changed\n").unwrap();
+    std::fs::write(tmp.path().join("notes.txt"), "new\n").unwrap();
+
+    let output = cargo_bin_cmd!("w")
+        .args(["__preview", tmp.path().to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w __preview failed: {output:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "main\n M README.md\n?? notes.txt\n"
+    );
+
+    // Previews must never fail, whatever path the picker hands over.
+    let missing = tmp.path().join("gone");
+    let output = cargo_bin_cmd!("w")
+        .args(["__preview", missing.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w __preview failed: {output:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!("{}: not a git worktree\n", missing.display())
+    );
+}
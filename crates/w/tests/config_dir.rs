@@ -0,0 +1,114 @@
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+
+fn git(current_dir: &Path, args: &[&str]) {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(current_dir)
+        .output()
+        .unwrap_or_else(|e| panic!("failed to run git {args:?}: {e}"));
+
+    if !output.status.success() {
+        panic!(
+            "git {args:?} failed\nstdout:\n{}\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+}
+
+fn init_repo(repo_dir: &Path) {
+    git(repo_dir, &["init", "-b", "main"]);
+    git(repo_dir, &["config", "user.name", "Test User"]);
+    git(repo_dir, &["config", "user.email", "test@example.com"]);
+
+    std::fs::write(repo_dir.join("README.md"), "hello\n").unwrap();
+    git(repo_dir, &["add", "README.md"]);
+    git(repo_dir, &["commit", "-m", "initial"]);
+}
+
+#[test]
+fn w_config_dir_holds_config_and_cache() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let root = tmp.path().join("root");
+    let repo = root.join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+
+    let config_dir = tmp.path().join("profile");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        format!(
+            "repo_roots = ['{}']\nmax_depth = 2\n\n[alias]\nrepos = \"repo index --format tsv\"\n",
+            root.display()
+        ),
+    )
+    .unwrap();
+    // The platform defaults point somewhere empty, so only `--config-dir` can find repos.
+    let elsewhere = tmp.path().join("elsewhere");
+
+    let w = |args: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .env("XDG_CONFIG_HOME", elsewhere.join("config"))
+            .env("XDG_CACHE_HOME", elsewhere.join("cache"))
+            .env_remove("W_CONFIG_DIR")
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w {args:?} failed: {output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    // An alias from the config in `--config-dir`, given before the alias name.
+    let stdout = w(&["--config-dir", config_dir.to_str().unwrap(), "repos"]);
+    assert!(
+        stdout.contains(repo.file_name().unwrap().to_str().unwrap()),
+        "{stdout}"
+    );
+    assert!(config_dir.join("cache").join("repo-index.json").is_file());
+    assert!(!elsewhere.exists());
+
+    let stdout = w(&[
+        "ls",
+        "--format",
+        "tsv",
+        "--config-dir",
+        config_dir.to_str().unwrap(),
+    ]);
+    assert_eq!(stdout.lines().count(), 1, "{stdout}");
+    assert!(!elsewhere.exists());
+}
+
+#[test]
+fn w_config_dir_flag_overrides_the_env_var() {
+    let tmp = tempfile::tempdir().unwrap();
+    let from_env = tmp.path().join("env");
+    let from_flag = tmp.path().join("flag");
+    for (dir, alias) in [
+        (&from_env, "shell init bash"),
+        (&from_flag, "shell init zsh"),
+    ] {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(
+            dir.join("config.toml"),
+            format!("[alias]\nsh = \"{alias}\"\n"),
+        )
+        .unwrap();
+    }
+
+    let run = |args: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .env("W_CONFIG_DIR", &from_env)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w {args:?} failed: {output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert!(run(&["sh"]).contains("(bash)"));
+    assert!(run(&["--config-dir", from_flag.to_str().unwrap(), "sh"]).contains("(zsh)"));
+}
@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+
+fn git(current_dir: &Path, args: &[&str]) {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(current_dir)
+        .output()
+        .unwrap_or_else(|e| panic!("failed to run git {args:?}: {e}"));
+
+    if !output.status.success() {
+        panic!(
+            "git {args:?} failed\nstdout:\n{}\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+}
+
+fn init_repo(repo_dir: &Path) {
+    git(repo_dir, &["init", "-b", "main"]);
+    git(repo_dir, &["config", "user.name", "Test User"]);
+    git(repo_dir, &["config", "user.email", "test@example.com"]);
+
+    std::fs::write(repo_dir.join("README.md"), "hello\n").unwrap();
+    git(repo_dir, &["add", "README.md"]);
+    git(repo_dir, &["commit", "-m", "initial"]);
+}
+
+#[test]
+fn w_diff_compares_against_default_branch_merge_base() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+
+    let wt = tmp.path().join("worktree_feature");
+    git(
+        &repo,
+        &["worktree", "add", "-b", "feature", wt.to_str().unwrap()],
+    );
+    std::fs::write(wt.join("feature.txt"), "feature\n").unwrap();
+    git(&wt, &["add", "feature.txt"]);
+    git(&wt, &["commit", "-m", "feature"]);
+
+    // Changes on the default branch after the fork point are excluded.
+    std::fs::write(repo.join("main.txt"), "main\n").unwrap();
+    git(&repo, &["add", "main.txt"]);
+    git(&repo, &["commit", "-m", "main"]);
+
+    let output = cargo_bin_cmd!("w")
+        .args([
+            "-C",
+            repo.to_str().unwrap(),
+            "diff",
+            "feature",
+            "--name-only",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w diff failed: {output:?}");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "feature.txt\n");
+
+    let output = cargo_bin_cmd!("w")
+        .args(["-C", repo.to_str().unwrap(), "diff", "feature", "--stat"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w diff failed: {output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("feature.txt"), "stdout: {stdout}");
+    assert!(stdout.contains("1 file changed"), "stdout: {stdout}");
+}
+
+#[test]
+fn w_diff_requires_existing_worktree() {
+    let tmp = tempfile::tempdir().unwrap();
+    init_repo(tmp.path());
+    git(tmp.path(), &["branch", "no-worktree"]);
+
+    let output = cargo_bin_cmd!("w")
+        .args(["-C", tmp.path().to_str().unwrap(), "diff", "no-worktree"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no worktree exists for branch no-worktree"),
+        "stderr: {stderr}"
+    );
+}
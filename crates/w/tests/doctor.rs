@@ -0,0 +1,152 @@
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+
+fn doctor(home: &Path, args: &[&str]) -> std::process::Output {
+    cargo_bin_cmd!("w")
+        .arg("doctor")
+        .args(args)
+        .current_dir(home)
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env("XDG_CACHE_HOME", home.join(".cache"))
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn w_doctor_fix_creates_config_and_cache_dir_after_confirmation() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    std::fs::create_dir_all(home.join("code")).unwrap();
+    let config = home.join(".config/w/config.toml");
+
+    let output = doctor(home, &[]);
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!(
+            "problem: config file {} does not exist (fix: w doctor --fix)",
+            config.display()
+        )),
+        "{stdout}"
+    );
+    assert!(stdout.contains("problem: cache directory"), "{stdout}");
+
+    // Without a terminal to confirm on, writing files needs --yes.
+    let output = doctor(home, &["--fix"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("pass --yes"), "{stderr}");
+    assert!(!config.exists());
+
+    let output = doctor(home, &["--fix", "--yes"]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!(
+            "fixed: created {} with repo_roots = [~/code]",
+            config.display()
+        )),
+        "{stdout}"
+    );
+    assert!(
+        std::fs::read_to_string(&config)
+            .unwrap()
+            .contains("repo_roots = [\"~/code\"]")
+    );
+    assert!(home.join(".cache/w").is_dir());
+
+    let output = doctor(home, &[]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("ok: config"), "{stdout}");
+}
+
+#[test]
+fn w_doctor_fix_rebuilds_a_corrupt_repo_index() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    std::fs::create_dir_all(home.join("code")).unwrap();
+    std::fs::create_dir_all(home.join(".config/w")).unwrap();
+    std::fs::write(
+        home.join(".config/w/config.toml"),
+        "repo_roots = [\"~/code\"]\n",
+    )
+    .unwrap();
+    let cache = home.join(".cache/w/repo-index.json");
+    std::fs::create_dir_all(cache.parent().unwrap()).unwrap();
+    std::fs::write(&cache, "{not json").unwrap();
+
+    let output = doctor(home, &[]);
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("failed to parse cache JSON"), "{stdout}");
+
+    let output = doctor(home, &["--fix", "--yes"]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("rebuilt the repo index (0 repos)"),
+        "{stdout}"
+    );
+    assert_eq!(
+        std::fs::read_to_string(home.join(".cache/w/repo-index.json.corrupt")).unwrap(),
+        "{not json"
+    );
+    let index: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&cache).unwrap()).unwrap();
+    assert_eq!(index["complete"], true);
+}
+
+#[test]
+fn w_doctor_leaves_unfixable_problems_to_the_user() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    std::fs::create_dir_all(home.join(".config/w")).unwrap();
+    std::fs::create_dir_all(home.join(".cache/w")).unwrap();
+    std::fs::write(home.join(".config/w/config.toml"), "repo_roots = []\n").unwrap();
+
+    let output = doctor(home, &["--fix", "--yes"]);
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("problem: no repo roots configured") && stdout.contains("not fixed"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn w_doctor_reports_an_unparseable_config() {
+    let tmp = tempfile::tempdir().unwrap();
+    let home = tmp.path();
+    let config = home.join(".config/w/config.toml");
+    std::fs::create_dir_all(config.parent().unwrap()).unwrap();
+    std::fs::write(&config, "repo_roots = [\n").unwrap();
+    std::fs::create_dir_all(home.join(".cache/w")).unwrap();
+
+    let output = doctor(home, &[]);
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!(
+            "problem: failed to parse TOML: {}",
+            config.display()
+        )),
+        "{stdout}"
+    );
+
+    // Commands that don't read the config still run, with a warning.
+    let output = cargo_bin_cmd!("w")
+        .args(["shell", "init", "bash"])
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("w: warning: max_concurrent_commands not applied"),
+        "{stderr}"
+    );
+}
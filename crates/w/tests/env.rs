@@ -0,0 +1,163 @@
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+
+fn git(current_dir: &Path, args: &[&str]) {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(current_dir)
+        .output()
+        .unwrap_or_else(|e| panic!("failed to run git {args:?}: {e}"));
+
+    if !output.status.success() {
+        panic!(
+            "git {args:?} failed\nstdout:\n{}\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+}
+
+fn init_repo(repo_dir: &Path) {
+    git(repo_dir, &["init", "-b", "main"]);
+    git(repo_dir, &["config", "user.name", "Test User"]);
+    git(repo_dir, &["config", "user.email", "test@example.com"]);
+
+    std::fs::write(repo_dir.join("README.md"), "hello\n").unwrap();
+    git(repo_dir, &["add", "README.md"]);
+    git(repo_dir, &["commit", "-m", "initial"]);
+}
+
+#[test]
+fn w_env_describes_the_current_worktree() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo_dir = tmp.path().join("my repo");
+    std::fs::create_dir(&repo_dir).unwrap();
+    init_repo(&repo_dir);
+    git(
+        &repo_dir,
+        &[
+            "remote",
+            "add",
+            "origin",
+            "https://github.com/acme/widgets.git",
+        ],
+    );
+    git(
+        &repo_dir,
+        &["worktree", "add", "-b", "feature", "../feature"],
+    );
+
+    let repo_root = dunce::canonicalize(&repo_dir).unwrap();
+    let worktree = dunce::canonicalize(tmp.path().join("feature")).unwrap();
+    let output = cargo_bin_cmd!("w")
+        .current_dir(&worktree)
+        .args(["env", "--export"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w env failed: {output:?}");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let expected = format!(
+        "export W_WORKTREE={}\n\
+         export W_BRANCH=feature\n\
+         export W_REPO='{}'\n\
+         export W_PROJECT=github.com/acme/widgets\n\
+         export W_DEFAULT_BRANCH=main\n\
+         export W_GIT_COMMON_DIR='{}'\n",
+        worktree.display(),
+        repo_root.display(),
+        repo_root.join(".git").display(),
+    );
+    assert_eq!(stdout, expected);
+}
+
+#[test]
+fn w_env_prints_nothing_outside_a_repo() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(tmp.path())
+        .env("GIT_CEILING_DIRECTORIES", tmp.path())
+        .arg("env")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w env failed: {output:?}");
+    assert!(output.stdout.is_empty(), "unexpected output: {output:?}");
+}
+
+#[test]
+fn w_env_follows_git_dir_and_git_work_tree() {
+    // A dotfiles-style setup: a bare repository whose work tree is a separate directory.
+    let tmp = tempfile::tempdir().unwrap();
+    let git_dir = tmp.path().join("dotfiles.git");
+    let home = tmp.path().join("home");
+    std::fs::create_dir_all(home.join("sub")).unwrap();
+    git(
+        tmp.path(),
+        &["init", "--bare", "-b", "main", git_dir.to_str().unwrap()],
+    );
+    std::fs::write(home.join(".bashrc"), "alias ll='ls -l'\n").unwrap();
+    for args in [
+        &["add", ".bashrc"][..],
+        &[
+            "-c",
+            "user.name=Test User",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "-m",
+            "initial",
+        ],
+    ] {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(&home)
+            .env("GIT_DIR", &git_dir)
+            .env("GIT_WORK_TREE", &home)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+    let other = tmp.path().join("other");
+    std::fs::create_dir(&other).unwrap();
+    init_repo(&other);
+
+    let run = |extra: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .current_dir(home.join("sub"))
+            // Relative values are resolved against the current directory, like git does.
+            .env("GIT_DIR", "../../dotfiles.git")
+            .env("GIT_WORK_TREE", "..")
+            .args(extra)
+            .arg("env")
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w env failed: {output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let home = dunce::canonicalize(&home).unwrap();
+    let stdout = run(&[]);
+    assert!(
+        stdout.contains(&format!("W_WORKTREE={}\n", home.display())),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains(&format!(
+            "W_GIT_COMMON_DIR={}\n",
+            dunce::canonicalize(&git_dir).unwrap().display()
+        )),
+        "{stdout}"
+    );
+
+    // `-C` names the repository explicitly, so it wins over the environment.
+    let stdout = run(&["-C", other.to_str().unwrap()]);
+    assert!(
+        stdout.contains(&format!(
+            "W_REPO={}\n",
+            dunce::canonicalize(&other).unwrap().display()
+        )),
+        "{stdout}"
+    );
+}
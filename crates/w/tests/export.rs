@@ -0,0 +1,125 @@
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use dunce::canonicalize;
+
+fn git(current_dir: &Path, args: &[&str]) {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(current_dir)
+        .output()
+        .unwrap_or_else(|e| panic!("failed to run git {args:?}: {e}"));
+
+    if !output.status.success() {
+        panic!(
+            "git {args:?} failed\nstdout:\n{}\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+}
+
+fn init_repo(repo_dir: &Path) {
+    git(repo_dir, &["init", "-b", "main"]);
+    git(repo_dir, &["config", "user.name", "Test User"]);
+    git(repo_dir, &["config", "user.email", "test@example.com"]);
+
+    std::fs::write(repo_dir.join("README.md"), "hello\n").unwrap();
+    git(repo_dir, &["add", "README.md"]);
+    git(repo_dir, &["commit", "-m", "initial"]);
+}
+
+#[test]
+fn w_export_records_remotes_and_worktree_branches() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let upstream = tmp.path().join("upstream");
+    std::fs::create_dir_all(&upstream).unwrap();
+    init_repo(&upstream);
+    git(&upstream, &["branch", "feature"]);
+
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    let clone = root.join("app");
+    git(
+        tmp.path(),
+        &["clone", upstream.to_str().unwrap(), clone.to_str().unwrap()],
+    );
+    let clone = canonicalize(&clone).unwrap();
+    let feature_wt = tmp.path().join("app.feature");
+    let local_wt = tmp.path().join("app.local");
+    let detached_wt = tmp.path().join("app.detached");
+    git(
+        &clone,
+        &[
+            "worktree",
+            "add",
+            "--track",
+            "-b",
+            "feature",
+            feature_wt.to_str().unwrap(),
+            "origin/feature",
+        ],
+    );
+    git(
+        &clone,
+        &["worktree", "add", "-b", "local", local_wt.to_str().unwrap()],
+    );
+    git(
+        &clone,
+        &["worktree", "add", "--detach", detached_wt.to_str().unwrap()],
+    );
+
+    let export = |extra: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .args(["export", "--root", root.to_str().unwrap()])
+            .args(["--max-depth", "2", "--cache-path"])
+            .arg(tmp.path().join("repo-index-cache.json"))
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w export failed: {output:?}");
+        output
+    };
+
+    let output = export(&[]);
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("w export: warning: skipping detached"),
+        "{output:?}"
+    );
+    let manifest: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let path = |p: &Path| canonicalize(p).unwrap().to_string_lossy().to_string();
+    assert_eq!(
+        manifest,
+        serde_json::json!({
+            "schema_version": 1,
+            "repos": [{
+                "project_identifier": manifest["repos"][0]["project_identifier"],
+                "path": path(&clone),
+                "remote": "origin",
+                "remote_url": upstream.to_str().unwrap(),
+                "default_branch": "main",
+                "worktrees": [
+                    {"branch": "feature", "upstream": "origin/feature", "path": path(&feature_wt)},
+                    {"branch": "local", "upstream": null, "path": path(&local_wt)},
+                ],
+            }],
+        })
+    );
+
+    let script = String::from_utf8(export(&["--format", "sh"]).stdout).unwrap();
+    assert!(script.starts_with("#!/bin/sh\n"), "{script}");
+    assert!(
+        script.contains(&format!(
+            "git clone --origin origin -- {} {}\n",
+            upstream.display(),
+            clone.display()
+        )),
+        "{script}"
+    );
+    assert!(
+        script.contains("new --print feature --base origin/feature --track origin/feature"),
+        "{script}"
+    );
+    assert!(script.contains("new --print local >/dev/null"), "{script}");
+}
@@ -0,0 +1,144 @@
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use dunce::canonicalize;
+
+fn git(current_dir: &Path, args: &[&str]) {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(current_dir)
+        .output()
+        .unwrap_or_else(|e| panic!("failed to run git {args:?}: {e}"));
+
+    if !output.status.success() {
+        panic!(
+            "git {args:?} failed\nstdout:\n{}\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+}
+
+fn init_repo(repo_dir: &Path) {
+    git(repo_dir, &["init", "-b", "main"]);
+    git(repo_dir, &["config", "user.name", "Test User"]);
+    git(repo_dir, &["config", "user.email", "test@example.com"]);
+
+    std::fs::write(repo_dir.join("README.md"), "hello\n").unwrap();
+    git(repo_dir, &["add", "README.md"]);
+    git(repo_dir, &["commit", "-m", "initial"]);
+}
+
+#[test]
+fn w_fetch_since_last_skips_recently_fetched_repos_unless_forced() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let upstream = tmp.path().join("upstream");
+    std::fs::create_dir_all(&upstream).unwrap();
+    init_repo(&upstream);
+
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    let clone = root.join("clone");
+    git(
+        tmp.path(),
+        &["clone", upstream.to_str().unwrap(), clone.to_str().unwrap()],
+    );
+    let clone = canonicalize(&clone).unwrap();
+    let cache_home = tmp.path().join("cache");
+
+    let fetch = |extra: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .env("XDG_CACHE_HOME", &cache_home)
+            .args(["fetch", "--root", root.to_str().unwrap()])
+            .args(["--max-depth", "2", "--cache-path"])
+            .arg(tmp.path().join("repo-index-cache.json"))
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w fetch failed: {output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    // Never fetched before: `--since-last` has nothing to skip.
+    assert_eq!(
+        fetch(&["--since-last", "1h"]),
+        format!("fetched {}\n", clone.display())
+    );
+    let state: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(cache_home.join("w").join("fetch-state.json")).unwrap(),
+    )
+    .unwrap();
+    assert!(
+        state["fetched_at"][clone.to_str().unwrap()].is_u64(),
+        "{state}"
+    );
+
+    let skipped = fetch(&["--since-last", "1h"]);
+    assert!(
+        skipped.starts_with(&format!("skipped {} (fetched ", clone.display())),
+        "{skipped}"
+    );
+    assert_eq!(
+        fetch(&["--since-last", "1h", "--force"]),
+        format!("fetched {}\n", clone.display())
+    );
+    assert_eq!(fetch(&[]), format!("fetched {}\n", clone.display()));
+}
+
+#[test]
+fn w_fetch_reports_failures_and_keeps_going() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let root = tmp.path().join("root");
+    let good = root.join("good");
+    let broken = root.join("broken");
+    std::fs::create_dir_all(&good).unwrap();
+    std::fs::create_dir_all(&broken).unwrap();
+    init_repo(&good);
+    init_repo(&broken);
+    git(
+        &broken,
+        &[
+            "remote",
+            "add",
+            "origin",
+            tmp.path().join("missing").to_str().unwrap(),
+        ],
+    );
+
+    let output = cargo_bin_cmd!("w")
+        .env("XDG_CACHE_HOME", tmp.path().join("cache"))
+        .args(["fetch", "--root", root.to_str().unwrap()])
+        .args(["--max-depth", "2", "--cache-path"])
+        .arg(tmp.path().join("repo-index-cache.json"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout,
+        format!("fetched {}\n", canonicalize(&good).unwrap().display())
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains(&format!(
+            "w fetch: {}: ",
+            canonicalize(&broken).unwrap().display()
+        )),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn w_fetch_rejects_invalid_durations() {
+    let output = cargo_bin_cmd!("w")
+        .args(["fetch", "--since-last", "10 minutes"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success(), "{output:?}");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("invalid duration"),
+        "{output:?}"
+    );
+}
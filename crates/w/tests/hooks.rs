@@ -0,0 +1,305 @@
+use std::path::{Path, PathBuf};
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use dunce::canonicalize;
+
+fn git(current_dir: &Path, args: &[&str]) {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(current_dir)
+        .output()
+        .unwrap_or_else(|e| panic!("failed to run git {args:?}: {e}"));
+
+    if !output.status.success() {
+        panic!(
+            "git {args:?} failed\nstdout:\n{}\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+}
+
+fn init_repo(repo_dir: &Path) {
+    git(repo_dir, &["init", "-b", "main"]);
+    git(repo_dir, &["config", "user.name", "Test User"]);
+    git(repo_dir, &["config", "user.email", "test@example.com"]);
+
+    std::fs::write(repo_dir.join("README.md"), "hello\n").unwrap();
+    git(repo_dir, &["add", "README.md"]);
+    git(repo_dir, &["commit", "-m", "initial"]);
+}
+
+fn parse_path(stdout: &[u8]) -> PathBuf {
+    let s = String::from_utf8(stdout.to_vec()).expect("stdout should be utf-8");
+    PathBuf::from(s.trim())
+}
+
+/// A repo whose global config logs every hook point to `<repo>/hooks.log`.
+fn repo_with_logging_hooks() -> (tempfile::TempDir, PathBuf, PathBuf) {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    std::fs::write(repo.join(".gitignore"), ".worktrees/\nhooks.log\n").unwrap();
+    git(&repo, &["add", ".gitignore"]);
+    git(&repo, &["commit", "-m", "ignore worktrees"]);
+
+    let config_home = tmp.path().join("config");
+    std::fs::create_dir_all(config_home.join("w")).unwrap();
+    let log = |hook: &str| format!("\"echo {hook} $W_BRANCH >> $W_REPO_PATH/hooks.log\"");
+    std::fs::write(
+        config_home.join("w").join("config.toml"),
+        format!(
+            "[hooks]\npost_create = [{}]\npost_switch = [{}]\npre_remove = [{}]\npost_remove = [{}]\n",
+            log("post_create"),
+            log("post_switch"),
+            log("pre_remove"),
+            log("post_remove"),
+        ),
+    )
+    .unwrap();
+    (tmp, repo, config_home)
+}
+
+fn w(repo: &Path, config_home: &Path, args: &[&str]) -> std::process::Output {
+    let output = cargo_bin_cmd!("w")
+        .current_dir(repo)
+        .env("XDG_CONFIG_HOME", config_home)
+        .env("XDG_CACHE_HOME", config_home.join("cache"))
+        .env(
+            "WORKTRUNK_WORKTREE_PATH",
+            ".worktrees/{{ branch | sanitize }}",
+        )
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w {args:?} failed: {output:?}");
+    output
+}
+
+fn hook_log(repo: &Path) -> String {
+    std::fs::read_to_string(repo.join("hooks.log")).unwrap_or_default()
+}
+
+#[test]
+fn w_hooks_list_layers_project_over_global() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let config_home = tmp.path().join("config");
+    std::fs::create_dir_all(config_home.join("w")).unwrap();
+    let global_path = config_home.join("w").join("config.toml");
+    let project = tmp.path().join("project");
+    let subdir = project.join("services");
+    std::fs::create_dir_all(&subdir).unwrap();
+    std::fs::write(
+        &global_path,
+        format!(
+            "trusted_projects = [{:?}]\n[hooks]\npost_create = [\"npm install\"]\n",
+            project.display().to_string()
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        project.join(".w.toml"),
+        "[hooks]\npost_create = [\"make setup\"]\non_failure = \"warn\"\n",
+    )
+    .unwrap();
+    let project_path = canonicalize(&project).unwrap().join(".w.toml");
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(&subdir)
+        .env("XDG_CONFIG_HOME", &config_home)
+        .args(["hooks", "list", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w hooks list failed: {output:?}");
+
+    let hooks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(hooks["on_failure"], "warn");
+    assert_eq!(hooks["on_failure_source"]["kind"], "project");
+    let post_create = hooks["post_create"].as_array().unwrap();
+    let listed = post_create
+        .iter()
+        .map(|hook| {
+            (
+                hook["command"].as_str().unwrap(),
+                hook["source"]["kind"].as_str().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        listed,
+        [("npm install", "global"), ("make setup", "project")]
+    );
+    assert_eq!(
+        canonicalize(post_create[1]["source"]["path"].as_str().unwrap()).unwrap(),
+        project_path
+    );
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(&subdir)
+        .env("XDG_CONFIG_HOME", &config_home)
+        .args(["hooks", "list"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w hooks list failed: {output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert!(
+        lines[0].starts_with("on_failure: warn (project: "),
+        "{stdout}"
+    );
+    assert_eq!(lines[1], "post_create:");
+    assert!(lines[2].starts_with("  npm install\t(global: "), "{stdout}");
+    assert!(lines[3].starts_with("  make setup\t(project: "), "{stdout}");
+}
+
+#[test]
+fn w_hooks_list_marks_untrusted_project_hooks_and_honors_config() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let config_path = tmp.path().join("custom.toml");
+    std::fs::write(&config_path, "[hooks]\npost_create = [\"npm install\"]\n").unwrap();
+
+    let project = tmp.path().join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(
+        project.join(".w.toml"),
+        "[hooks]\npost_create = [\"make setup\"]\non_failure = \"warn\"\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(&project)
+        .env("XDG_CONFIG_HOME", tmp.path().join("config"))
+        .args(["hooks", "list", "--json", "--config"])
+        .arg(&config_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w hooks list failed: {output:?}");
+
+    let hooks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    // An untrusted project can't change the failure policy either.
+    assert_eq!(hooks["on_failure"], "abort");
+    let listed = hooks["post_create"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hook| {
+            (
+                hook["command"].as_str().unwrap(),
+                hook["source"]["kind"].as_str().unwrap(),
+                hook["trusted"].as_bool().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        listed,
+        [
+            ("npm install", "global", true),
+            ("make setup", "project", false)
+        ]
+    );
+}
+
+#[test]
+fn w_hooks_list_reports_defaults_without_config() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(tmp.path())
+        .env("XDG_CONFIG_HOME", tmp.path().join("config"))
+        .args(["hooks", "list"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w hooks list failed: {output:?}");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "on_failure: abort (default)\n\
+         post_create:\n  (none)\n\
+         post_switch:\n  (none)\n\
+         pre_remove:\n  (none)\n\
+         post_remove:\n  (none)\n"
+    );
+}
+
+#[test]
+fn w_new_runs_post_create_but_cd_to_existing_runs_post_switch() {
+    let (_tmp, repo, config_home) = repo_with_logging_hooks();
+
+    let created = parse_path(&w(&repo, &config_home, &["new", "feature"]).stdout);
+    assert_eq!(hook_log(&repo), "post_create feature\n");
+
+    let switched = parse_path(&w(&repo, &config_home, &["cd", "feature"]).stdout);
+    assert_eq!(switched, created);
+    assert_eq!(
+        hook_log(&repo),
+        "post_create feature\npost_switch feature\n"
+    );
+
+    w(&repo, &config_home, &["cd", "feature", "--no-hooks"]);
+    assert_eq!(
+        hook_log(&repo),
+        "post_create feature\npost_switch feature\n"
+    );
+}
+
+#[test]
+fn w_switch_history_runs_post_switch_like_a_picked_worktree() {
+    let (_tmp, repo, config_home) = repo_with_logging_hooks();
+    let created = parse_path(&w(&repo, &config_home, &["new", "feature", "--no-hooks"]).stdout);
+    // `w cd` records the visit (and runs post_switch itself).
+    w(&repo, &config_home, &["cd", "feature"]);
+    assert_eq!(hook_log(&repo), "post_switch feature\n");
+
+    let switched = parse_path(
+        &w(
+            &repo,
+            &config_home,
+            &["switch", "--history", "--filter", "feature"],
+        )
+        .stdout,
+    );
+    assert_eq!(switched, canonicalize(&created).unwrap());
+    assert_eq!(
+        hook_log(&repo),
+        "post_switch feature\npost_switch feature\n"
+    );
+
+    w(
+        &repo,
+        &config_home,
+        &["switch", "--history", "--filter", "feature", "--no-hooks"],
+    );
+    assert_eq!(
+        hook_log(&repo),
+        "post_switch feature\npost_switch feature\n"
+    );
+}
+
+#[test]
+fn w_new_detach_runs_only_post_create() {
+    let (_tmp, repo, config_home) = repo_with_logging_hooks();
+
+    w(&repo, &config_home, &["new", "--detach", "HEAD"]);
+    assert_eq!(hook_log(&repo), "post_create\n");
+}
+
+#[test]
+fn w_cd_runs_post_create_when_it_creates_the_worktree() {
+    let (_tmp, repo, config_home) = repo_with_logging_hooks();
+    git(&repo, &["branch", "feature"]);
+
+    w(&repo, &config_home, &["cd", "feature"]);
+    assert_eq!(hook_log(&repo), "post_create feature\n");
+}
+
+#[test]
+fn w_rm_runs_pre_remove_then_post_remove() {
+    let (_tmp, repo, config_home) = repo_with_logging_hooks();
+    let path = parse_path(&w(&repo, &config_home, &["new", "feature", "--no-hooks"]).stdout);
+
+    w(&repo, &config_home, &["rm", "feature"]);
+    assert!(!path.exists());
+    assert_eq!(hook_log(&repo), "pre_remove feature\npost_remove feature\n");
+}
@@ -0,0 +1,225 @@
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use dunce::canonicalize;
+
+fn git(current_dir: &Path, args: &[&str]) {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(current_dir)
+        .output()
+        .unwrap_or_else(|e| panic!("failed to run git {args:?}: {e}"));
+
+    if !output.status.success() {
+        panic!(
+            "git {args:?} failed\nstdout:\n{}\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+}
+
+fn init_repo(repo_dir: &Path) {
+    git(repo_dir, &["init", "-b", "main"]);
+    git(repo_dir, &["config", "user.name", "Test User"]);
+    git(repo_dir, &["config", "user.email", "test@example.com"]);
+
+    std::fs::write(repo_dir.join("README.md"), "hello\n").unwrap();
+    git(repo_dir, &["add", "README.md"]);
+    git(repo_dir, &["commit", "-m", "initial"]);
+}
+
+#[test]
+fn w_import_clones_repos_and_recreates_worktrees_idempotently() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let upstream = tmp.path().join("upstream");
+    std::fs::create_dir_all(&upstream).unwrap();
+    init_repo(&upstream);
+    git(&upstream, &["branch", "feature"]);
+
+    let manifest = serde_json::json!({
+        "schema_version": 1,
+        "repos": [
+            {
+                "project_identifier": "app",
+                "path": "/elsewhere/src/app",
+                "remote": "upstream",
+                "remote_url": upstream.to_str().unwrap(),
+                "default_branch": "main",
+                "worktrees": [
+                    {"branch": "feature", "upstream": "upstream/feature", "path": "/elsewhere/src/app.feature"},
+                ],
+            },
+            {
+                "project_identifier": "gone",
+                "path": "/elsewhere/src/gone",
+                "remote": null,
+                "remote_url": null,
+                "default_branch": null,
+                "worktrees": [],
+            },
+        ],
+    });
+    let manifest_path = tmp.path().join("manifest.json");
+    std::fs::write(&manifest_path, manifest.to_string()).unwrap();
+    let into = tmp.path().join("src");
+    std::fs::create_dir_all(&into).unwrap();
+    // Hooks from the importing machine's config only run with `--run-hooks`.
+    let config_dir = tmp.path().join("config").join("w");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        "[hooks]\npost_create = [\"touch hook-ran\"]\n",
+    )
+    .unwrap();
+
+    let import = |extra: &[&str]| {
+        cargo_bin_cmd!("w")
+            .env("XDG_CONFIG_HOME", tmp.path().join("config"))
+            .env(
+                "WORKTRUNK_WORKTREE_PATH",
+                ".worktrees/{{ branch | sanitize }}",
+            )
+            .arg("import")
+            .arg(&manifest_path)
+            .arg("--into")
+            .arg(&into)
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+
+    // A repo with no remote URL to clone from fails without stopping the others.
+    let output = import(&["--dry-run"]);
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    let app = into.join("app");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "would clone {} to {}\nwould create feature in {}\n",
+            upstream.display(),
+            app.display(),
+            app.display()
+        )
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains(&format!("w import: {}: ", into.join("gone").display())),
+        "{stderr}"
+    );
+    assert!(!app.exists());
+
+    let output = import(&[]);
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let app = canonicalize(&app).unwrap();
+    let feature_wt = app.join(".worktrees").join("feature");
+    assert_eq!(
+        stdout,
+        format!(
+            "cloned {}\ncreated {}\n",
+            into.join("app").display(),
+            feature_wt.display()
+        )
+    );
+    let branches = std::process::Command::new("git")
+        .args([
+            "for-each-ref",
+            "--format=%(refname:short) %(upstream:short)",
+        ])
+        .arg("refs/heads/feature")
+        .current_dir(&app)
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(branches.stdout).unwrap(),
+        "feature upstream/feature\n"
+    );
+    assert!(!feature_wt.join("hook-ran").exists());
+
+    // Running it again finds everything in place.
+    let output = import(&[]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "exists {}\nexists {}\n",
+            into.join("app").display(),
+            feature_wt.display()
+        )
+    );
+}
+
+#[test]
+fn w_import_rejects_clashing_targets_and_clones_of_other_repos() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let upstream = tmp.path().join("upstream");
+    std::fs::create_dir_all(&upstream).unwrap();
+    init_repo(&upstream);
+    let other = tmp.path().join("other");
+    std::fs::create_dir_all(&other).unwrap();
+    init_repo(&other);
+
+    let into = tmp.path().join("src");
+    std::fs::create_dir_all(&into).unwrap();
+    // Already at `--into`'s target, but cloned from a different repo.
+    git(
+        tmp.path(),
+        &[
+            "clone",
+            "--origin",
+            "upstream",
+            "--",
+            other.to_str().unwrap(),
+            into.join("lib").to_str().unwrap(),
+        ],
+    );
+
+    let repo = |path: &str| {
+        serde_json::json!({
+            "project_identifier": path,
+            "path": path,
+            "remote": "upstream",
+            "remote_url": upstream.to_str().unwrap(),
+            "default_branch": "main",
+            "worktrees": [],
+        })
+    };
+    let manifest = serde_json::json!({
+        "schema_version": 1,
+        "repos": [repo("/work/app"), repo("/personal/app"), repo("/work/lib")],
+    });
+    let manifest_path = tmp.path().join("manifest.json");
+    std::fs::write(&manifest_path, manifest.to_string()).unwrap();
+
+    let output = cargo_bin_cmd!("w")
+        .env("XDG_CONFIG_HOME", tmp.path().join("config"))
+        .arg("import")
+        .arg(&manifest_path)
+        .arg("--into")
+        .arg(&into)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let app = into.join("app");
+    for recorded in ["/work/app", "/personal/app"] {
+        assert!(
+            stderr.contains(&format!(
+                "w import: {}: {recorded} maps to the same path as another repo in the manifest",
+                app.display()
+            )),
+            "{stderr}"
+        );
+    }
+    assert!(
+        stderr.contains(&format!(
+            "w import: {}: exists, but its upstream remote is ",
+            into.join("lib").display()
+        )),
+        "{stderr}"
+    );
+    assert!(!app.exists());
+}
@@ -415,6 +415,45 @@ fn w_ls_accepts_max_concurrent_repos_in_config() {
     assert!(output.status.success(), "w ls failed: {output:?}");
 }
 
+#[test]
+fn w_ls_shares_max_concurrent_commands_budget_across_repos() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let root = tmp.path().join("root");
+    for name in ["repo_a", "repo_b", "repo_c"] {
+        let repo = root.join(name);
+        std::fs::create_dir_all(&repo).unwrap();
+        init_repo(&repo);
+    }
+
+    let config_path = tmp.path().join("w-config.toml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "repo_roots = ['{}']\nmax_concurrent_repos = 8\nmax_concurrent_commands = 1\n",
+            root.display()
+        ),
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("w")
+        .args([
+            "ls",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--cache-path",
+            tmp.path().join("repo-index-cache.json").to_str().unwrap(),
+            "--format",
+            "json",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let parsed: LsOutput = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed.worktrees.len(), 3, "{parsed:?}");
+    assert!(parsed.errors.is_empty(), "{parsed:?}");
+}
+
 #[test]
 fn w_ls_text_preset_can_be_set_via_config() {
     let tmp = tempfile::tempdir().unwrap();
@@ -450,7 +489,7 @@ fn w_ls_text_preset_can_be_set_via_config() {
 
     for line in lines {
         let cols = line.split('\t').collect::<Vec<_>>();
-        assert_eq!(cols.len(), 5, "expected 5 columns for full preset");
+        assert_eq!(cols.len(), 9, "expected 9 columns for full preset");
     }
 }
 
@@ -564,3 +603,2337 @@ fn w_ls_sort_project_orders_by_project_identifier() {
     assert_eq!(project_ids[2], "github.com/z/repo");
     assert_eq!(project_ids[3], "github.com/z/repo");
 }
+
+#[test]
+fn w_ls_sort_supports_per_key_direction_and_reverse() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let root = tmp.path().join("root");
+    for (name, owner) in [("repo_a", "z"), ("repo_b", "a")] {
+        let repo = root.join(name);
+        std::fs::create_dir_all(&repo).unwrap();
+        init_repo(&repo);
+        git(
+            &repo,
+            &[
+                "remote",
+                "add",
+                "origin",
+                &format!("https://github.com/{owner}/repo"),
+            ],
+        );
+        let wt = tmp.path().join(format!("worktree_{name}"));
+        git(
+            &repo,
+            &["worktree", "add", "-b", name, wt.to_str().unwrap()],
+        );
+    }
+
+    let branches = |extra: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .args(["ls", "--root", root.to_str().unwrap()])
+            .args(["--max-depth", "2", "--no-project"])
+            .arg("--cache-path")
+            .arg(tmp.path().join("repo-index-cache.json"))
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(|line| line.split('\t').next().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    // github.com/z/repo (repo_a) sorts first when the project key is descending.
+    assert_eq!(
+        branches(&["--sort", "project:desc,path"]),
+        ["main", "repo_a", "main", "repo_b"]
+    );
+    assert_eq!(
+        branches(&["--sort", "project:desc,path:desc"]),
+        ["repo_a", "main", "repo_b", "main"]
+    );
+    assert_eq!(
+        branches(&["--sort", "project", "--reverse"]),
+        branches(&["--sort", "project:desc,path:desc"])
+    );
+}
+
+#[test]
+fn w_ls_watch_requires_tty() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let root = init_root_repo_with_feature_worktree(&tmp);
+    let cache_path = tmp.path().join("repo-index-cache.json");
+
+    let output = cargo_bin_cmd!("w")
+        .args([
+            "ls",
+            "--root",
+            root.to_str().unwrap(),
+            "--max-depth",
+            "2",
+            "--cache-path",
+            cache_path.to_str().unwrap(),
+            "--watch",
+        ])
+        .output()
+        .unwrap();
+
+    assert!(
+        !output.status.success(),
+        "expected failure, got: {output:?}"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--watch requires a TTY"),
+        "stderr did not mention --watch:\n{stderr}"
+    );
+}
+
+#[test]
+fn w_ls_sync_reports_default_branch_divergence() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let root = tmp.path().join("root");
+    let repo = root.join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+
+    let wt = tmp.path().join("worktree_feature");
+    git(
+        &repo,
+        &["worktree", "add", "-b", "feature", wt.to_str().unwrap()],
+    );
+    git(&wt, &["commit", "--allow-empty", "-m", "feature work"]);
+    git(&repo, &["commit", "--allow-empty", "-m", "main moved"]);
+
+    let run = |extra: &[&str]| {
+        let mut args = vec!["-C", repo.to_str().unwrap(), "ls", "--format", "json"];
+        args.extend_from_slice(extra);
+        let output = cargo_bin_cmd!("w").args(&args).output().unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+
+    let plain = run(&[]);
+    for wt in plain["worktrees"].as_array().unwrap() {
+        assert!(wt["default_branch_behind"].is_null(), "{wt}");
+        assert!(wt["needs_rebase"].is_null(), "{wt}");
+        assert!(wt["divergence"].is_null(), "{wt}");
+    }
+
+    let synced = run(&["--sync"]);
+    let worktrees = synced["worktrees"].as_array().unwrap();
+    let by_branch = |branch: &str| {
+        worktrees
+            .iter()
+            .find(|wt| wt["branch"] == branch)
+            .unwrap_or_else(|| panic!("missing {branch}: {worktrees:?}"))
+    };
+
+    let main = by_branch("main");
+    assert_eq!(main["default_branch_ahead"], 0);
+    assert_eq!(main["default_branch_behind"], 0);
+    assert_eq!(main["needs_rebase"], false);
+    assert_eq!(main["divergence"], "even");
+
+    let feature = by_branch("feature");
+    assert_eq!(feature["default_branch_ahead"], 1);
+    assert_eq!(feature["default_branch_behind"], 1);
+    assert_eq!(feature["needs_rebase"], true);
+    assert_eq!(feature["divergence"], "diverged");
+}
+
+#[test]
+fn w_ls_json_counts_are_null_unless_measured_and_numbers_when_they_are() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    let unborn = tmp.path().join("unborn");
+    std::fs::create_dir_all(&unborn).unwrap();
+    git(&unborn, &["init", "-b", "main"]);
+
+    let counts = [
+        "default_branch_ahead",
+        "default_branch_behind",
+        "commit_count",
+    ];
+    let run = |repo: &Path, extra: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .args(["-C", repo.to_str().unwrap(), "ls", "--format", "json"])
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["worktrees"][0].clone()
+    };
+
+    // Not asked for: `null`, never `0`; `--minimal` drops them.
+    let wt = run(&repo, &[]);
+    for count in counts {
+        assert!(wt[count].is_null(), "{count}: {wt}");
+    }
+    let wt = run(&repo, &["--minimal"]);
+    for count in counts {
+        assert!(wt.get(count).is_none(), "{count}: {wt}");
+    }
+
+    // Measured zeros are numbers, and `--minimal` keeps them.
+    for extra in [
+        &["--sync", "--commit-count"][..],
+        &["--sync", "--commit-count", "--minimal"],
+    ] {
+        let wt = run(&repo, extra);
+        for count in counts {
+            assert_eq!(wt[count].as_u64(), Some(0), "{count}: {wt}");
+        }
+    }
+
+    // Asked for but not measurable (no commit to compare): still `null`.
+    let wt = run(&unborn, &["--sync", "--commit-count"]);
+    for count in counts {
+        assert!(wt[count].is_null(), "{count}: {wt}");
+    }
+}
+
+#[test]
+fn w_ls_sync_flags_branches_whose_upstream_is_gone() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    git(
+        &repo,
+        &[
+            "remote",
+            "add",
+            "origin",
+            "https://example.invalid/repo.git",
+        ],
+    );
+
+    for branch in ["merged", "open"] {
+        let wt = tmp.path().join(format!("worktree_{branch}"));
+        git(
+            &repo,
+            &["worktree", "add", "-b", branch, wt.to_str().unwrap()],
+        );
+        git(
+            &repo,
+            &["config", &format!("branch.{branch}.remote"), "origin"],
+        );
+        git(
+            &repo,
+            &[
+                "config",
+                &format!("branch.{branch}.merge"),
+                &format!("refs/heads/{branch}"),
+            ],
+        );
+    }
+    // Only `open` still has its remote-tracking branch; `merged`'s was deleted.
+    git(&repo, &["update-ref", "refs/remotes/origin/open", "HEAD"]);
+
+    let run = |extra: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .args(["-C", repo.to_str().unwrap(), "ls"])
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let plain: serde_json::Value = serde_json::from_str(&run(&["--format", "json"])).unwrap();
+    for wt in plain["worktrees"].as_array().unwrap() {
+        assert!(wt["upstream_gone"].is_null(), "{wt}");
+    }
+
+    let synced: serde_json::Value =
+        serde_json::from_str(&run(&["--format", "json", "--sync"])).unwrap();
+    let worktrees = synced["worktrees"].as_array().unwrap();
+    let upstream_gone = |branch: &str| {
+        worktrees
+            .iter()
+            .find(|wt| wt["branch"] == branch)
+            .unwrap_or_else(|| panic!("missing {branch}: {worktrees:?}"))["upstream_gone"]
+            .clone()
+    };
+    assert_eq!(upstream_gone("merged"), true);
+    assert_eq!(upstream_gone("open"), false);
+    // No upstream configured at all isn't "gone".
+    assert_eq!(upstream_gone("main"), false);
+
+    let stdout = run(&["--preset", "full", "--sync"]);
+    let upstream_column = |branch: &str| {
+        stdout
+            .lines()
+            .find(|line| line.contains(&format!("\t{branch}\t")))
+            .unwrap_or_else(|| panic!("missing {branch} row:\n{stdout}"))
+            .split('\t')
+            .nth(5)
+            .map(str::to_string)
+    };
+    assert_eq!(
+        upstream_column("merged").as_deref(),
+        Some("origin/merged [gone]")
+    );
+    assert_eq!(upstream_column("open").as_deref(), Some("origin/open"));
+}
+
+#[test]
+fn w_ls_describe_names_what_detached_worktrees_have_checked_out() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    git(&repo, &["tag", "v1.2.0"]);
+
+    let wt = tmp.path().join("worktree_release");
+    git(
+        &repo,
+        &[
+            "worktree",
+            "add",
+            "--detach",
+            wt.to_str().unwrap(),
+            "v1.2.0",
+        ],
+    );
+
+    let run = |extra: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .args(["-C", repo.to_str().unwrap(), "ls"])
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&["--format", "json", "--describe"])).unwrap();
+    let worktrees = json["worktrees"].as_array().unwrap();
+    let detached = worktrees
+        .iter()
+        .find(|wt| wt["detached"] == true)
+        .unwrap_or_else(|| panic!("{json}"));
+    assert_eq!(detached["head_describe"], "tags/v1.2.0");
+    // Worktrees on a branch are already named by it.
+    let main = worktrees.iter().find(|wt| wt["branch"] == "main").unwrap();
+    assert!(main["head_describe"].is_null(), "{json}");
+
+    let json: serde_json::Value = serde_json::from_str(&run(&["--format", "json"])).unwrap();
+    assert!(
+        json["worktrees"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|wt| wt["head_describe"].is_null()),
+        "{json}"
+    );
+
+    let text = run(&["--describe"]);
+    assert!(text.contains("(detached tags/v1.2.0)"), "{text}");
+}
+
+#[test]
+fn w_ls_reports_worktrees_on_the_default_branch() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    // Move the default branch out of the main worktree and into a linked one.
+    git(&repo, &["checkout", "--detach"]);
+    let wt_main = tmp.path().join("worktree_main");
+    git(
+        &repo,
+        &["worktree", "add", wt_main.to_str().unwrap(), "main"],
+    );
+    let wt_feature = tmp.path().join("worktree_feature");
+    git(
+        &repo,
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "feature",
+            wt_feature.to_str().unwrap(),
+        ],
+    );
+
+    let output = cargo_bin_cmd!("w")
+        .args(["-C", repo.to_str().unwrap(), "ls", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let flags = json["worktrees"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|wt| {
+            (
+                wt["branch"].as_str().unwrap_or("(detached)").to_string(),
+                wt["is_primary"].as_bool().unwrap(),
+                wt["is_default_branch"].as_bool().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        flags.contains(&("(detached)".into(), true, false)),
+        "{flags:?}"
+    );
+    assert!(flags.contains(&("main".into(), false, true)), "{flags:?}");
+    assert!(
+        flags.contains(&("feature".into(), false, false)),
+        "{flags:?}"
+    );
+    // A plain listing works the default branch out without caching it in git config.
+    let cached = std::process::Command::new("git")
+        .args(["config", "--get", "worktrunk.default-branch"])
+        .current_dir(&repo)
+        .output()
+        .unwrap();
+    assert!(!cached.status.success(), "{cached:?}");
+
+    let output = cargo_bin_cmd!("w")
+        .args(["-C", repo.to_str().unwrap(), "ls", "--preset", "full"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let default_column = |branch: &str| {
+        stdout
+            .lines()
+            .find(|line| line.contains(&format!("\t{branch}\t")))
+            .unwrap_or_else(|| panic!("missing {branch} row:\n{stdout}"))
+            .split('\t')
+            .nth(8)
+            .map(str::to_string)
+    };
+    assert_eq!(default_column("main").as_deref(), Some("default"));
+    assert_eq!(default_column("feature").as_deref(), Some(""));
+}
+
+#[test]
+fn w_ls_commit_count_counts_commits_since_the_default_branch() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+
+    let wt = tmp.path().join("worktree_feature");
+    git(
+        &repo,
+        &["worktree", "add", "-b", "feature", wt.to_str().unwrap()],
+    );
+    git(&wt, &["commit", "--allow-empty", "-m", "feature work"]);
+    git(&wt, &["commit", "--allow-empty", "-m", "more feature work"]);
+    // Commits landing on main afterwards don't count towards the branch.
+    git(&repo, &["commit", "--allow-empty", "-m", "main moved"]);
+
+    let run = |extra: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .args(["-C", repo.to_str().unwrap(), "ls"])
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&["--format", "json", "--commit-count"])).unwrap();
+    let worktrees = json["worktrees"].as_array().unwrap();
+    let count = |branch: &str| {
+        worktrees
+            .iter()
+            .find(|wt| wt["branch"] == branch)
+            .unwrap_or_else(|| panic!("missing {branch}: {worktrees:?}"))["commit_count"]
+            .clone()
+    };
+    assert_eq!(count("main"), 0);
+    assert_eq!(count("feature"), 2);
+    // `--sync` fields stay off unless asked for.
+    assert!(worktrees[0]["default_branch_ahead"].is_null());
+
+    let json: serde_json::Value = serde_json::from_str(&run(&["--format", "json"])).unwrap();
+    assert!(json["worktrees"][0]["commit_count"].is_null(), "{json}");
+
+    let text = run(&["--preset", "full", "--commit-count"]);
+    let feature_line = text
+        .lines()
+        .find(|line| line.contains("\tfeature\t"))
+        .unwrap_or_else(|| panic!("{text}"));
+    assert!(feature_line.ends_with("\t2"), "{feature_line:?}");
+    let text = run(&["--preset", "full"]);
+    let feature_line = text
+        .lines()
+        .find(|line| line.contains("\tfeature\t"))
+        .unwrap();
+    assert!(!feature_line.ends_with("\t2"), "{feature_line:?}");
+}
+
+#[test]
+fn w_ls_ahead_of_filters_to_worktrees_with_unshipped_commits() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    git(&repo, &["branch", "release"]);
+
+    let feature = tmp.path().join("worktree_feature");
+    git(
+        &repo,
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "feature",
+            feature.to_str().unwrap(),
+        ],
+    );
+    git(&feature, &["commit", "--allow-empty", "-m", "feature one"]);
+    git(&feature, &["commit", "--allow-empty", "-m", "feature two"]);
+    let shipped = tmp.path().join("worktree_shipped");
+    git(
+        &repo,
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "shipped",
+            shipped.to_str().unwrap(),
+        ],
+    );
+
+    let run = |format: &str| {
+        let output = cargo_bin_cmd!("w")
+            .args([
+                "-C",
+                repo.to_str().unwrap(),
+                "ls",
+                "--ahead-of",
+                "release",
+                "--format",
+                format,
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        output.stdout
+    };
+
+    let json: serde_json::Value = serde_json::from_slice(&run("json")).unwrap();
+    let worktrees = json["worktrees"].as_array().unwrap();
+    assert_eq!(worktrees.len(), 1, "{worktrees:?}");
+    assert_eq!(worktrees[0]["branch"], "feature");
+    assert_eq!(worktrees[0]["ahead_of_count"], 2);
+
+    let text = String::from_utf8(run("text")).unwrap();
+    assert!(text.trim_end().ends_with("\t+2"), "{text}");
+
+    let output = cargo_bin_cmd!("w")
+        .args([
+            "-C",
+            repo.to_str().unwrap(),
+            "ls",
+            "--ahead-of",
+            "no-such-ref",
+            "--format",
+            "json",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["worktrees"].as_array().unwrap().is_empty(), "{json}");
+}
+
+#[test]
+fn w_ls_behind_combines_with_ahead_of_for_divergence() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+
+    let stale = tmp.path().join("worktree_stale");
+    git(
+        &repo,
+        &["worktree", "add", "-b", "stale", stale.to_str().unwrap()],
+    );
+    let diverged = tmp.path().join("worktree_diverged");
+    git(
+        &repo,
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "diverged",
+            diverged.to_str().unwrap(),
+        ],
+    );
+    git(
+        &diverged,
+        &["commit", "--allow-empty", "-m", "diverged work"],
+    );
+    git(&repo, &["commit", "--allow-empty", "-m", "main one"]);
+    git(&repo, &["commit", "--allow-empty", "-m", "main two"]);
+
+    let run = |extra: &[&str]| {
+        let mut args = vec!["-C", repo.to_str().unwrap(), "ls"];
+        args.extend_from_slice(extra);
+        let output = cargo_bin_cmd!("w").args(&args).output().unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        output.stdout
+    };
+    let branches = |stdout: &[u8]| {
+        let json: serde_json::Value = serde_json::from_slice(stdout).unwrap();
+        let mut worktrees = json["worktrees"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|wt| {
+                (
+                    wt["branch"].as_str().unwrap().to_string(),
+                    wt["ahead_of_count"].clone(),
+                    wt["behind_count"].clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        worktrees.sort_by(|a, b| a.0.cmp(&b.0));
+        worktrees
+    };
+
+    assert_eq!(
+        branches(&run(&["--behind", "main", "--format", "json"])),
+        [
+            (
+                "diverged".to_string(),
+                serde_json::Value::Null,
+                serde_json::json!(2)
+            ),
+            (
+                "stale".to_string(),
+                serde_json::Value::Null,
+                serde_json::json!(2)
+            ),
+        ]
+    );
+    assert_eq!(
+        branches(&run(&[
+            "--behind",
+            "main",
+            "--ahead-of",
+            "main",
+            "--format",
+            "json"
+        ])),
+        [
+            (
+                "diverged".to_string(),
+                serde_json::json!(1),
+                serde_json::json!(2)
+            ),
+            // Only behind, but the two filters are a union.
+            (
+                "stale".to_string(),
+                serde_json::json!(0),
+                serde_json::json!(2)
+            ),
+        ]
+    );
+
+    let text = String::from_utf8(run(&["--behind", "main", "--ahead-of", "main"])).unwrap();
+    assert!(
+        text.lines().any(|line| line.ends_with("\t+1\t-2")),
+        "{text}"
+    );
+    assert!(
+        text.lines().any(|line| line.ends_with("\t+0\t-2")),
+        "{text}"
+    );
+    assert_eq!(text.lines().count(), 2, "{text}");
+}
+
+#[test]
+fn w_ls_formats_project_identifier_for_text_output_only() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let root = tmp.path().join("root");
+    let repo = root.join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    git(
+        &repo,
+        &[
+            "remote",
+            "add",
+            "origin",
+            "https://github.com/org/widgets.git",
+        ],
+    );
+
+    let run = |format_template: &str, output_format: &str| {
+        let config_path = tmp.path().join("w-config.toml");
+        std::fs::write(
+            &config_path,
+            format!("project_identifier_format = {format_template:?}\n"),
+        )
+        .unwrap();
+        cargo_bin_cmd!("w")
+            .args([
+                "ls",
+                "--config",
+                config_path.to_str().unwrap(),
+                "--root",
+                root.to_str().unwrap(),
+                "--cache-path",
+                tmp.path().join("repo-index.json").to_str().unwrap(),
+                "--format",
+                output_format,
+            ])
+            .output()
+            .unwrap()
+    };
+    let text = |format_template: &str| {
+        let output = run(format_template, "text");
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert!(
+        text("{host}/{owner}/{repo}").starts_with("github.com/org/widgets\tmain\t"),
+        "default template should keep the canonical identifier"
+    );
+    assert!(text("{owner}/{repo}").starts_with("org/widgets\tmain\t"));
+    assert!(text("{repo} ({host})").starts_with("widgets (github.com)\tmain\t"));
+
+    let output = run("{repo}", "json");
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json["worktrees"][0]["project_identifier"],
+        "github.com/org/widgets"
+    );
+
+    let output = run("{org}/{repo}", "text");
+    assert!(!output.status.success(), "expected failure: {output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("unknown placeholder {org}"), "{stderr}");
+}
+
+#[test]
+fn w_ls_truncates_long_branch_names_in_text_output_only() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let root = tmp.path().join("root");
+    let repo = root.join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    let branch = "dependabot/npm_and_yarn/lodash-4.17.21";
+    git(&repo, &["checkout", "-q", "-b", branch]);
+
+    let config_path = tmp.path().join("w-config.toml");
+    std::fs::write(&config_path, "max_branch_display_width = 26\n").unwrap();
+    let run = |output_format: &str| {
+        let output = cargo_bin_cmd!("w")
+            .args([
+                "ls",
+                "--config",
+                config_path.to_str().unwrap(),
+                "--root",
+                root.to_str().unwrap(),
+                "--cache-path",
+                tmp.path().join("repo-index.json").to_str().unwrap(),
+                "--format",
+                output_format,
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let text = run("text");
+    let fields: Vec<&str> = text.lines().next().unwrap().split('\t').collect();
+    assert_eq!(fields[1], "dependabot/npm_and_yarn/…", "{text}");
+
+    let json: serde_json::Value = serde_json::from_str(&run("json")).unwrap();
+    assert_eq!(json["worktrees"][0]["branch"], branch);
+    assert!(run("tsv").contains(branch));
+}
+
+#[test]
+fn w_ls_contains_returns_innermost_worktree_for_path() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let root = tmp.path().join("root");
+    let repo = root.join("repo");
+    let other = root.join("other");
+    std::fs::create_dir_all(&repo).unwrap();
+    std::fs::create_dir_all(&other).unwrap();
+    init_repo(&repo);
+    init_repo(&other);
+
+    // Nested inside the main worktree, so both roots prefix files under it.
+    let nested = repo.join(".worktrees").join("feature");
+    git(
+        &repo,
+        &["worktree", "add", "-b", "feature", nested.to_str().unwrap()],
+    );
+    std::fs::create_dir_all(nested.join("src")).unwrap();
+
+    let contains = |path: &Path, format: &str| {
+        cargo_bin_cmd!("w")
+            .args([
+                "ls",
+                "--root",
+                root.to_str().unwrap(),
+                "--cache-path",
+                tmp.path().join("repo-index.json").to_str().unwrap(),
+                "--format",
+                format,
+                "--contains",
+                path.to_str().unwrap(),
+            ])
+            .output()
+            .unwrap()
+    };
+    let branches = |path: &Path| {
+        let output = contains(path, "json");
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["worktrees"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|wt| wt["branch"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        branches(&nested.join("src").join("new_file.rs")),
+        ["feature"]
+    );
+    assert_eq!(branches(&repo.join("README.md")), ["main"]);
+
+    let output = contains(&nested, "tsv");
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 1, "{stdout}");
+    assert!(stdout.contains("\tfeature\t"), "{stdout}");
+
+    let output = contains(tmp.path(), "json");
+    assert!(!output.status.success(), "expected failure: {output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("no worktree contains"), "{stderr}");
+}
+
+#[test]
+fn w_ls_csv_quotes_fields_and_supports_header() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let root = tmp.path().join("root");
+    let repo = root.join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+
+    let wt = tmp.path().join("worktree,feature");
+    git(
+        &repo,
+        &["worktree", "add", "-b", "feature", wt.to_str().unwrap()],
+    );
+
+    let output = cargo_bin_cmd!("w")
+        .args([
+            "-C",
+            repo.to_str().unwrap(),
+            "ls",
+            "--format",
+            "csv",
+            "--header",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 3, "expected header + 2 rows, got: {lines:?}");
+    assert_eq!(
+        lines[0],
+        "project_identifier,repo_path,path,branch,head,detached,locked,prunable"
+    );
+
+    let wt = canonicalize(&wt).unwrap().to_string_lossy().to_string();
+    let feature = lines
+        .iter()
+        .find(|line| line.contains(",feature,"))
+        .unwrap_or_else(|| panic!("missing feature row: {lines:?}"));
+    assert!(
+        feature.contains(&format!("\"{wt}\"")),
+        "expected quoted path in: {feature}"
+    );
+}
+
+#[test]
+fn w_ls_includes_branch_description() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+
+    let wt = tmp.path().join("worktree_feature");
+    git(
+        &repo,
+        &["worktree", "add", "-b", "feature", wt.to_str().unwrap()],
+    );
+    git(
+        &repo,
+        &[
+            "config",
+            "branch.feature.description",
+            "Long-lived spike\nsecond line",
+        ],
+    );
+
+    let output = cargo_bin_cmd!("w")
+        .args(["-C", repo.to_str().unwrap(), "ls", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+
+    let out: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let worktrees = out["worktrees"].as_array().unwrap();
+    let description = |branch: &str| {
+        worktrees
+            .iter()
+            .find(|wt| wt["branch"] == branch)
+            .unwrap_or_else(|| panic!("missing {branch}: {worktrees:?}"))["description"]
+            .clone()
+    };
+    assert_eq!(description("feature"), "Long-lived spike\nsecond line");
+    assert_eq!(description("main"), serde_json::Value::Null);
+
+    let output = cargo_bin_cmd!("w")
+        .args(["-C", repo.to_str().unwrap(), "ls", "--preset", "full"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let feature = stdout
+        .lines()
+        .find(|line| line.contains("\tfeature\t"))
+        .unwrap_or_else(|| panic!("missing feature row:\n{stdout}"));
+    assert_eq!(feature.split('\t').nth(6), Some("Long-lived spike"));
+}
+
+#[test]
+fn w_ls_reports_configured_upstream() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+
+    let wt = tmp.path().join("worktree_feature");
+    git(
+        &repo,
+        &["worktree", "add", "-b", "feature", wt.to_str().unwrap()],
+    );
+    git(&repo, &["config", "branch.feature.remote", "origin"]);
+    git(
+        &repo,
+        &["config", "branch.feature.merge", "refs/heads/feature"],
+    );
+
+    let output = cargo_bin_cmd!("w")
+        .args(["-C", repo.to_str().unwrap(), "ls", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+
+    let out: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let worktrees = out["worktrees"].as_array().unwrap();
+    let upstream = |branch: &str| {
+        worktrees
+            .iter()
+            .find(|wt| wt["branch"] == branch)
+            .unwrap_or_else(|| panic!("missing {branch}: {worktrees:?}"))["upstream"]
+            .clone()
+    };
+    assert_eq!(upstream("feature"), "origin/feature");
+    assert_eq!(upstream("main"), serde_json::Value::Null);
+
+    let output = cargo_bin_cmd!("w")
+        .args(["-C", repo.to_str().unwrap(), "ls", "--preset", "full"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let upstream_column = |branch: &str| {
+        stdout
+            .lines()
+            .find(|line| line.contains(&format!("\t{branch}\t")))
+            .unwrap_or_else(|| panic!("missing {branch} row:\n{stdout}"))
+            .split('\t')
+            .nth(5)
+            .map(str::to_string)
+    };
+    assert_eq!(
+        upstream_column("feature").as_deref(),
+        Some("origin/feature")
+    );
+    assert_eq!(upstream_column("main").as_deref(), Some(""));
+}
+
+#[test]
+fn w_ls_discovers_project_w_toml_from_subdirectory() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let project = tmp.path().join("project");
+    let root = project.join("root");
+    let repo = root.join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    let wt = tmp.path().join("worktree_feature");
+    git(
+        &repo,
+        &["worktree", "add", "-b", "feature", wt.to_str().unwrap()],
+    );
+    std::fs::write(
+        project.join(".w.toml"),
+        "repo_roots = [\"root\"]\nmax_depth = 2\n",
+    )
+    .unwrap();
+    let subdir = project.join("docs").join("nested");
+    std::fs::create_dir_all(&subdir).unwrap();
+
+    let config_home = tmp.path().join("config");
+    let cache_home = tmp.path().join("cache");
+    std::fs::create_dir_all(&config_home).unwrap();
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(&subdir)
+        .env("XDG_CONFIG_HOME", &config_home)
+        .env("XDG_CACHE_HOME", &cache_home)
+        .args(["ls", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+
+    let out: LsOutput = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(out.worktrees.len(), 2, "got: {:?}", out.worktrees);
+    assert!(
+        out.worktrees
+            .iter()
+            .all(|wt| wt.repo_path == canonicalize(&repo).unwrap().to_string_lossy())
+    );
+    // The project gets its own index instead of sharing the global one.
+    assert!(!cache_home.join("w").join("repo-index.json").exists());
+    assert!(cache_home.join("w").join("projects").is_dir());
+
+    // `-C` finds the project's `.w.toml` (and its index) from outside the project too.
+    std::fs::remove_dir_all(cache_home.join("w")).unwrap();
+    let output = cargo_bin_cmd!("w")
+        .current_dir(tmp.path())
+        .env("XDG_CONFIG_HOME", &config_home)
+        .env("XDG_CACHE_HOME", &cache_home)
+        .args(["-C", repo.to_str().unwrap(), "repo", "index"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w repo index failed: {output:?}");
+    let index: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        index["repos"][0]["path"],
+        canonicalize(&repo).unwrap().to_str().unwrap()
+    );
+    assert!(!cache_home.join("w").join("repo-index.json").exists());
+    assert!(cache_home.join("w").join("projects").is_dir());
+
+    // CLI roots still win over `.w.toml`.
+    let empty_root = tmp.path().join("empty");
+    std::fs::create_dir_all(&empty_root).unwrap();
+    let output = cargo_bin_cmd!("w")
+        .current_dir(&subdir)
+        .env("XDG_CONFIG_HOME", &config_home)
+        .env("XDG_CACHE_HOME", &cache_home)
+        .args([
+            "ls",
+            "--format",
+            "json",
+            "--root",
+            empty_root.to_str().unwrap(),
+            "--cache-path",
+            tmp.path().join("cli-cache.json").to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let out: LsOutput = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(out.worktrees.is_empty(), "got: {:?}", out.worktrees);
+}
+
+#[test]
+fn w_ls_full_preset_colors_locked_and_prunable_only_when_asked() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+
+    let locked = tmp.path().join("worktree_locked");
+    git(
+        &repo,
+        &["worktree", "add", "-b", "locked", locked.to_str().unwrap()],
+    );
+    git(
+        &repo,
+        &[
+            "worktree",
+            "lock",
+            "--reason",
+            "on usb",
+            locked.to_str().unwrap(),
+        ],
+    );
+    let gone = tmp.path().join("worktree_gone");
+    git(
+        &repo,
+        &["worktree", "add", "-b", "gone", gone.to_str().unwrap()],
+    );
+    std::fs::remove_dir_all(&gone).unwrap();
+
+    let ls_full = |extra: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .args([
+                "-C",
+                repo.to_str().unwrap(),
+                "ls",
+                "--preset",
+                "full",
+                "--include-prunable",
+            ])
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let row = |stdout: &str, branch: &str| {
+        stdout
+            .lines()
+            .find(|line| line.contains(&format!("\t{branch}\t")))
+            .unwrap_or_else(|| panic!("missing {branch} row:\n{stdout}"))
+            .split('\t')
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    // Piped output is plain by default.
+    let plain = ls_full(&[]);
+    assert!(!plain.contains('\x1b'), "unexpected escapes:\n{plain}");
+    assert_eq!(row(&plain, "locked")[3], "on usb");
+
+    let colored = ls_full(&["--color", "always"]);
+    let locked_row = row(&colored, "locked");
+    assert_eq!(locked_row.len(), 9);
+    assert_eq!(locked_row[3], "\x1b[2mon usb\x1b[0m");
+    let gone_row = row(&colored, "gone");
+    assert!(
+        gone_row[4].starts_with("\x1b[33m") && gone_row[4].ends_with("\x1b[0m"),
+        "prunable cell not colored: {gone_row:?}"
+    );
+    // Unstyled cells stay empty rather than carrying bare escapes.
+    assert_eq!(row(&colored, "main")[3], "");
+}
+
+#[cfg(unix)]
+#[test]
+fn w_ls_no_canonicalize_keeps_symlinked_worktree_paths() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+
+    // Move the worktree's directory aside and leave a symlink where git recorded it.
+    let recorded = canonicalize(tmp.path()).unwrap().join("feature");
+    git(
+        &repo,
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "feature",
+            recorded.to_str().unwrap(),
+        ],
+    );
+    let storage = recorded.with_file_name("feature-storage");
+    std::fs::rename(&recorded, &storage).unwrap();
+    std::os::unix::fs::symlink(&storage, &recorded).unwrap();
+
+    let feature_path = |extra_args: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .args(["-C", repo.to_str().unwrap(), "ls", "--format", "json"])
+            .args(extra_args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["worktrees"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|wt| wt["branch"] == "feature")
+            .map(|wt| wt["path"].as_str().unwrap().to_string())
+            .unwrap()
+    };
+
+    assert_eq!(feature_path(&[]), storage.to_str().unwrap());
+    assert_eq!(
+        feature_path(&["--no-canonicalize"]),
+        recorded.to_str().unwrap()
+    );
+
+    // `--contains` still matches files reached through either path.
+    let contains = storage.join("README.md");
+    assert_eq!(
+        feature_path(&[
+            "--no-canonicalize",
+            "--contains",
+            contains.to_str().unwrap()
+        ]),
+        recorded.to_str().unwrap()
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn w_ls_pr_reports_open_pull_request_from_gh() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    git(
+        &repo,
+        &[
+            "remote",
+            "add",
+            "origin",
+            "https://github.com/acme/widgets.git",
+        ],
+    );
+    git(&repo, &["config", "remote.pushDefault", "origin"]);
+    let feature = tmp.path().join("feature");
+    git(
+        &repo,
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "feature",
+            feature.to_str().unwrap(),
+        ],
+    );
+
+    // A stand-in `gh` that reports one open PR for `feature` and none for other branches.
+    let bin = tmp.path().join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let gh = bin.join("gh");
+    std::fs::write(
+        &gh,
+        r#"#!/bin/sh
+case "$*" in
+  --version) echo "gh version 2.0.0" ;;
+  *"--head feature "*) echo '[{"number":42,"url":"https://github.com/acme/widgets/pull/42","headRepositoryOwner":{"login":"acme"},"statusCheckRollup":[]}]' ;;
+  *) echo '[]' ;;
+esac
+"#,
+    )
+    .unwrap();
+    std::fs::set_permissions(&gh, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    let pr_fields = |extra_args: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .env("PATH", &path)
+            .args(["-C", repo.to_str().unwrap(), "ls", "--format", "json"])
+            .args(extra_args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["worktrees"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|wt| {
+                (
+                    wt["branch"].as_str().unwrap().to_string(),
+                    wt["pr_url"].clone(),
+                    wt["pr_number"].clone(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut with_pr = pr_fields(&["--pr"]);
+    with_pr.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        with_pr,
+        [
+            (
+                "feature".to_string(),
+                serde_json::json!("https://github.com/acme/widgets/pull/42"),
+                serde_json::json!(42)
+            ),
+            (
+                "main".to_string(),
+                serde_json::Value::Null,
+                serde_json::Value::Null
+            ),
+        ]
+    );
+
+    assert!(
+        pr_fields(&[])
+            .iter()
+            .all(|(_, url, number)| url.is_null() && number.is_null())
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn w_ls_ci_reports_semantic_status_with_separate_display() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    git(
+        &repo,
+        &[
+            "remote",
+            "add",
+            "origin",
+            "https://github.com/acme/widgets.git",
+        ],
+    );
+    git(&repo, &["config", "remote.pushDefault", "origin"]);
+    let feature = tmp.path().join("feature");
+    git(
+        &repo,
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "feature",
+            feature.to_str().unwrap(),
+        ],
+    );
+
+    // A stand-in `gh` whose only open PR (for `feature`) has passing checks.
+    let bin = tmp.path().join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let gh = bin.join("gh");
+    std::fs::write(
+        &gh,
+        r#"#!/bin/sh
+case "$*" in
+  --version) echo "gh version 2.0.0" ;;
+  *"--head feature "*) echo '[{"number":7,"url":"https://github.com/acme/widgets/pull/7","headRepositoryOwner":{"login":"acme"},"statusCheckRollup":[{"status":"COMPLETED","conclusion":"SUCCESS"}]}]' ;;
+  *) echo '[]' ;;
+esac
+"#,
+    )
+    .unwrap();
+    std::fs::set_permissions(&gh, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    let worktrees = |extra_args: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .env("PATH", &path)
+            .args(["-C", repo.to_str().unwrap(), "ls", "--format", "json"])
+            .args(extra_args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let mut worktrees = json["worktrees"].as_array().unwrap().clone();
+        worktrees.sort_by_key(|wt| wt["branch"].as_str().unwrap().to_string());
+        worktrees
+    };
+
+    let with_ci = worktrees(&["--ci"]);
+    assert_eq!(with_ci[0]["branch"], "feature");
+    assert_eq!(with_ci[0]["ci_status"], "passed");
+    // `--ci` alone doesn't fill the PR fields.
+    assert!(with_ci[0]["pr_url"].is_null());
+    let display = with_ci[0]["display"]["ci"].as_str().unwrap();
+    assert!(
+        display.contains('●') && display.contains('\x1b'),
+        "{display}"
+    );
+    assert!(with_ci[1]["ci_status"].is_null());
+    assert!(with_ci[1]["display"].is_null());
+
+    for wt in worktrees(&[]) {
+        assert!(wt["ci_status"].is_null());
+        assert!(wt["display"].is_null());
+    }
+}
+
+#[test]
+fn w_ls_no_project_drops_the_project_column_from_text() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = init_root_repo_with_feature_worktree(&tmp);
+    let repo = root.join("repo");
+
+    let ls = |args: &[&str]| {
+        cargo_bin_cmd!("w")
+            .args(["-C", repo.to_str().unwrap(), "ls", "--sort", "path"])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    let output = ls(&["--no-project"]);
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let repo_path = canonicalize(&repo).unwrap();
+    let wt_path = canonicalize(tmp.path().join("worktree_feature")).unwrap();
+    assert_eq!(
+        stdout,
+        format!(
+            "main\t{}\nfeature\t{}\n",
+            repo_path.display(),
+            wt_path.display()
+        )
+    );
+
+    let output = ls(&["--no-project", "--preset", "compact"]);
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "main\nfeature\n");
+
+    let output = ls(&["--no-project", "--preset", "full"]);
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.lines().all(|line| line.split('\t').count() == 8),
+        "{stdout}"
+    );
+
+    let output = ls(&["--no-project", "--format", "tsv"]);
+    assert!(!output.status.success(), "expected failure: {output:?}");
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("--no-project is only supported with --format text"),
+        "{output:?}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn w_ls_sigint_flushes_partial_json_and_exits_130() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path().join("root");
+    for i in 0..8 {
+        let repo = root.join(format!("repo_{i}"));
+        std::fs::create_dir_all(&repo).unwrap();
+        init_repo(&repo);
+    }
+    let cache_path = tmp.path().join("repo-index.json");
+    let ls_args = [
+        "ls",
+        "--root",
+        root.to_str().unwrap(),
+        "--cache-path",
+        cache_path.to_str().unwrap(),
+        "--jobs",
+        "1",
+        "--format",
+        "json",
+    ];
+    let output = cargo_bin_cmd!("w").args(ls_args).output().unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+
+    // A `git` that takes a while per call, so the scan is still running when interrupted.
+    let bin = tmp.path().join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let real_git = String::from_utf8(
+        std::process::Command::new("sh")
+            .args(["-c", "command -v git"])
+            .output()
+            .unwrap()
+            .stdout,
+    )
+    .unwrap();
+    let slow_git = bin.join("git");
+    std::fs::write(
+        &slow_git,
+        format!("#!/bin/sh\nsleep 0.3\nexec {} \"$@\"\n", real_git.trim()),
+    )
+    .unwrap();
+    std::fs::set_permissions(&slow_git, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    let child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("w"))
+        .env("PATH", &path)
+        .args(ls_args)
+        .arg("--cached")
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(1500));
+    let status = std::process::Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let output = child.wait_with_output().unwrap();
+
+    assert_eq!(output.status.code(), Some(130), "{output:?}");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("interrupted; output is partial"),
+        "{output:?}"
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["partial"], true);
+    let listed = json["worktrees"].as_array().unwrap().len();
+    assert!(
+        listed < 8,
+        "expected a partial listing, got all {listed} worktrees"
+    );
+}
+
+#[test]
+fn w_ls_repo_path_relative_to_rewrites_paths_under_base() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = init_root_repo_with_feature_worktree(&tmp);
+    let repo = root.join("repo");
+
+    let ls = |base: &Path| {
+        let output = cargo_bin_cmd!("w")
+            .args(["-C", repo.to_str().unwrap(), "ls", "--format", "json"])
+            .args(["--sort", "path", "--repo-path-relative-to"])
+            .arg(base)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        let parsed: LsOutput = serde_json::from_slice(&output.stdout).unwrap();
+        parsed
+            .worktrees
+            .into_iter()
+            .map(|wt| (wt.repo_path, wt.path))
+            .collect::<Vec<_>>()
+    };
+
+    // The feature worktree lives outside `root`, so its path stays absolute.
+    let wt_path = canonicalize(tmp.path().join("worktree_feature")).unwrap();
+    assert_eq!(
+        ls(&root),
+        vec![
+            ("repo".to_string(), "repo".to_string()),
+            ("repo".to_string(), wt_path.display().to_string()),
+        ]
+    );
+
+    assert_eq!(
+        ls(tmp.path()),
+        vec![
+            ("root/repo".to_string(), "root/repo".to_string()),
+            ("root/repo".to_string(), "worktree_feature".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn w_ls_include_main_only_keeps_one_primary_worktree_per_repo() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path().join("root");
+    let repo_a = root.join("repo_a");
+    let repo_b = root.join("repo_b");
+    for (repo, feature) in [(&repo_a, "feature-a"), (&repo_b, "feature-b")] {
+        std::fs::create_dir_all(repo).unwrap();
+        init_repo(repo);
+        let wt = tmp.path().join(format!("worktree_{feature}"));
+        git(
+            repo,
+            &["worktree", "add", "-b", feature, wt.to_str().unwrap()],
+        );
+    }
+    // Primary is decided by path, not by which branch is checked out there.
+    git(&repo_b, &["switch", "-c", "topic"]);
+
+    let output = cargo_bin_cmd!("w")
+        .args(["ls", "--root", root.to_str().unwrap()])
+        .args([
+            "--max-depth",
+            "2",
+            "--include-main-only",
+            "--format",
+            "json",
+        ])
+        .arg("--cache-path")
+        .arg(tmp.path().join("repo-index-cache.json"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+
+    let out: LsOutput = serde_json::from_slice(&output.stdout).unwrap();
+    let mut actual = out
+        .worktrees
+        .into_iter()
+        .map(|wt| (wt.repo_path, wt.path, wt.branch))
+        .collect::<Vec<_>>();
+    actual.sort();
+
+    let repo_a = canonicalize(&repo_a).unwrap().to_string_lossy().to_string();
+    let repo_b = canonicalize(&repo_b).unwrap().to_string_lossy().to_string();
+    assert_eq!(
+        actual,
+        vec![
+            (repo_a.clone(), repo_a, Some("main".to_string())),
+            (repo_b.clone(), repo_b, Some("topic".to_string())),
+        ]
+    );
+}
+
+#[test]
+fn w_ls_marks_the_primary_worktree() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    let sibling = tmp.path().join("sibling");
+    git(
+        &repo,
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "sibling",
+            sibling.to_str().unwrap(),
+        ],
+    );
+    // Nested under the primary's directory, but still a linked worktree.
+    git(
+        &repo,
+        &["worktree", "add", "-b", "nested", ".worktrees/nested"],
+    );
+
+    // Listing from a linked worktree still finds the repo's primary.
+    let output = cargo_bin_cmd!("w")
+        .args(["-C", sibling.to_str().unwrap(), "ls", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let mut flags = json["worktrees"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|wt| {
+            (
+                wt["branch"].as_str().unwrap().to_string(),
+                wt["is_primary"].as_bool().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    flags.sort();
+    assert_eq!(
+        flags,
+        [
+            ("main".to_string(), true),
+            ("nested".to_string(), false),
+            ("sibling".to_string(), false),
+        ]
+    );
+}
+
+#[test]
+fn w_ls_json_is_compact_when_piped_unless_pretty() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = init_root_repo_with_feature_worktree(&tmp);
+
+    let run = |extra: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .args(["ls", "--root", root.to_str().unwrap()])
+            .args(["--max-depth", "2", "--format", "json"])
+            .args(extra)
+            .arg("--cache-path")
+            .arg(tmp.path().join("repo-index-cache.json"))
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    // stdout is a pipe here, so the default is compact.
+    for stdout in [run(&[]), run(&["--compact"])] {
+        assert_eq!(stdout.lines().count(), 1, "{stdout}");
+        let out: LsOutput = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(out.worktrees.len(), 2);
+    }
+
+    let pretty = run(&["--pretty"]);
+    assert!(pretty.lines().count() > 1, "{pretty}");
+    assert!(pretty.contains("\n  \"schema_version\""), "{pretty}");
+    let out: LsOutput = serde_json::from_str(&pretty).unwrap();
+    assert_eq!(out.worktrees.len(), 2);
+
+    let output = cargo_bin_cmd!("w")
+        .args(["ls", "--root", root.to_str().unwrap(), "--pretty"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--pretty and --compact are only supported with --format json"),
+        "{stderr}"
+    );
+
+    assert!(
+        cargo_bin_cmd!("w")
+            .args(["ls", "--format", "json", "--pretty", "--compact"])
+            .output()
+            .unwrap()
+            .status
+            .code()
+            == Some(2)
+    );
+}
+
+#[test]
+fn w_ls_lists_exactly_the_repos_given_with_repeated_repo() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut repos = Vec::new();
+    for name in ["a", "b", "c"] {
+        let repo = tmp.path().join(name);
+        std::fs::create_dir_all(&repo).unwrap();
+        init_repo(&repo);
+        repos.push(repo);
+    }
+    let wt = tmp.path().join("a-feature");
+    git(
+        &repos[0],
+        &["worktree", "add", "-b", "feature", wt.to_str().unwrap()],
+    );
+    let plain = tmp.path().join("plain");
+    std::fs::create_dir_all(&plain).unwrap();
+
+    let output = cargo_bin_cmd!("w")
+        .args(["ls", "--format", "json"])
+        .args(["--repo", repos[0].to_str().unwrap()])
+        .args(["--repo", repos[1].to_str().unwrap()])
+        // A subdirectory of an already-listed repo doesn't list it twice.
+        .args(["-C", wt.to_str().unwrap()])
+        // Not a repo: reported as an error without hiding the others.
+        .args(["-C", plain.to_str().unwrap()])
+        .env("XDG_CACHE_HOME", tmp.path().join("cache"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let out: LsOutput = serde_json::from_slice(&output.stdout).unwrap();
+    let listed = out
+        .worktrees
+        .iter()
+        .map(|wt| (wt.repo_path.clone(), wt.branch.clone().unwrap()))
+        .collect::<Vec<_>>();
+    let repo_path = |repo: &Path| canonicalize(repo).unwrap().to_string_lossy().to_string();
+    assert_eq!(
+        listed,
+        vec![
+            (repo_path(&repos[0]), "main".to_string()),
+            (repo_path(&repos[0]), "feature".to_string()),
+            (repo_path(&repos[1]), "main".to_string()),
+        ]
+    );
+    assert_eq!(out.errors.len(), 1, "{:?}", out.errors);
+    assert_eq!(out.errors[0]["repo_path"], plain.to_str().unwrap());
+    assert!(
+        out.errors[0]["error"]
+            .as_str()
+            .unwrap()
+            .contains("failed to discover git repo"),
+        "{:?}",
+        out.errors
+    );
+    assert!(
+        !tmp.path().join("cache").exists(),
+        "index should be skipped"
+    );
+
+    let output = cargo_bin_cmd!("w")
+        .args(["-C", repos[0].to_str().unwrap(), "ls"])
+        .args(["--root", tmp.path().to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("can't be combined with --root"), "{stderr}");
+
+    let output = cargo_bin_cmd!("w")
+        .args(["-C", repos[0].to_str().unwrap()])
+        .args(["-C", repos[1].to_str().unwrap(), "path", "main"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("-C/--repo can only be repeated with w ls"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn w_ls_repo_meta_adds_one_entry_per_repo() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = init_root_repo_with_feature_worktree(&tmp);
+    let repo = root.join("repo");
+
+    let run = |extra: &[&str]| {
+        cargo_bin_cmd!("w")
+            .args(["ls", "--root", root.to_str().unwrap()])
+            .args(["--max-depth", "2"])
+            .args(extra)
+            .arg("--cache-path")
+            .arg(tmp.path().join("repo-index-cache.json"))
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&["--format", "json", "--repo-meta"]);
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let head = std::process::Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(&repo)
+        .output()
+        .unwrap();
+    let repo_path = canonicalize(&repo).unwrap().to_string_lossy().to_string();
+    let objects_size = json["repos"][0]["objects_size_bytes"].as_u64();
+    // The initial commit's objects take at least one KiB block.
+    assert!(objects_size.is_some_and(|size| size >= 1024), "{json}");
+    assert_eq!(
+        json["repos"],
+        serde_json::json!([{
+            "project_identifier": repo_path,
+            "path": repo_path,
+            "default_branch": "main",
+            "head": String::from_utf8_lossy(&head.stdout).trim(),
+            "objects_size_bytes": objects_size,
+        }])
+    );
+    assert_eq!(json["worktrees"].as_array().unwrap().len(), 2);
+
+    let output = run(&["--format", "json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["repos"].is_null(), "{json}");
+
+    let output = run(&["--repo-meta"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--repo-meta is only supported with --format json"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn w_ls_schema_version_pins_json_output() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = init_root_repo_with_feature_worktree(&tmp);
+
+    let run = |extra: &[&str]| {
+        cargo_bin_cmd!("w")
+            .args(["ls", "--root", root.to_str().unwrap()])
+            .args(["--max-depth", "2"])
+            .args(extra)
+            .arg("--cache-path")
+            .arg(tmp.path().join("repo-index-cache.json"))
+            .output()
+            .unwrap()
+    };
+
+    let latest = run(&["--format", "json"]);
+    let pinned = run(&["--format", "json", "--schema-version", "1"]);
+    assert!(pinned.status.success(), "w ls failed: {pinned:?}");
+    assert_eq!(pinned.stdout, latest.stdout);
+    let out: LsOutput = serde_json::from_slice(&pinned.stdout).unwrap();
+    assert_eq!(out.schema_version, 1);
+
+    let output = run(&["--format", "json", "--schema-version", "99"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("unsupported schema version 99 (supported: 1)"),
+        "{stderr}"
+    );
+
+    let output = run(&["--schema-version", "1"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--schema-version is only supported with --format json"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn w_ls_timeout_total_reports_unlisted_repos_as_partial() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = init_root_repo_with_feature_worktree(&tmp);
+
+    let run_with_stderr = |budget: &str| {
+        let output = cargo_bin_cmd!("w")
+            .args(["--quiet", "ls", "--root", root.to_str().unwrap()])
+            .args(["--max-depth", "2", "--format", "json"])
+            .args(["--timeout-total", budget])
+            .arg("--cache-path")
+            .arg(tmp.path().join("repo-index-cache.json"))
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        (
+            serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap(),
+            String::from_utf8(output.stderr).unwrap(),
+        )
+    };
+    let run = |budget: &str| run_with_stderr(budget).0;
+
+    // A zero budget runs out before any repo is dispatched. Unlisted repos are errors,
+    // which `--quiet` still reports.
+    let (out, stderr) = run_with_stderr("0");
+    assert!(
+        stderr.contains(": not listed: --timeout-total of 0s elapsed"),
+        "{stderr}"
+    );
+    assert_eq!(out["partial"], true, "{out}");
+    assert_eq!(out["worktrees"].as_array().unwrap().len(), 0, "{out}");
+    let errors = out["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 1, "{out}");
+    assert_eq!(
+        errors[0]["error"],
+        "not listed: --timeout-total of 0s elapsed"
+    );
+
+    let out = run("60");
+    assert_eq!(out["partial"], false, "{out}");
+    assert_eq!(out["worktrees"].as_array().unwrap().len(), 2, "{out}");
+    assert_eq!(out["errors"].as_array().unwrap().len(), 0, "{out}");
+
+    assert!(
+        !cargo_bin_cmd!("w")
+            .args(["ls", "--timeout-total", "-1"])
+            .output()
+            .unwrap()
+            .status
+            .success()
+    );
+
+    let output = cargo_bin_cmd!("w")
+        .args(["ls", "--timeout-total", "1", "--watch"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("cannot be used with"), "{stderr}");
+}
+
+#[test]
+fn w_ls_by_host_counts_repos_and_worktrees_per_remote_host() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = init_root_repo_with_feature_worktree(&tmp);
+    git(
+        &root.join("repo"),
+        &["remote", "add", "origin", "git@github.com:owner/repo.git"],
+    );
+    let other = root.join("other");
+    std::fs::create_dir_all(&other).unwrap();
+    init_repo(&other);
+
+    git(
+        &tmp.path().join("worktree_feature"),
+        &["commit", "--allow-empty", "-m", "feature work"],
+    );
+
+    let run = |format: &str, extra: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .args(["ls", "--root", root.to_str().unwrap()])
+            .args(["--max-depth", "2", "--by-host", "--format", format])
+            .arg("--cache-path")
+            .arg(tmp.path().join("repo-index-cache.json"))
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let out: serde_json::Value = serde_json::from_str(&run("json", &[])).unwrap();
+    assert_eq!(
+        out["hosts"],
+        serde_json::json!({
+            "github.com": { "repos": 1, "worktrees": 2 },
+            "local": { "repos": 1, "worktrees": 1 },
+        })
+    );
+    assert_eq!(out["errors"], serde_json::json!([]));
+
+    assert_eq!(run("text", &[]), "github.com\t1\t2\nlocal\t1\t1\n");
+
+    // A repo whose worktrees are all filtered out still counts, with no worktrees.
+    assert_eq!(
+        run("text", &["--ahead-of", "main"]),
+        "github.com\t1\t1\nlocal\t1\t0\n"
+    );
+}
+
+#[test]
+fn w_ls_reports_worktree_creation_time() {
+    let now = || {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    };
+    let started = now();
+
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    let wt = tmp.path().join("worktree_feature");
+    git(
+        &repo,
+        &["worktree", "add", "-b", "feature", wt.to_str().unwrap()],
+    );
+
+    let output = cargo_bin_cmd!("w")
+        .args(["-C", repo.to_str().unwrap(), "ls", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let out: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let worktrees = out["worktrees"].as_array().unwrap();
+    assert_eq!(worktrees.len(), 2);
+    let finished = now();
+    for wt in worktrees {
+        let created_at = wt["created_at"]
+            .as_u64()
+            .unwrap_or_else(|| panic!("missing created_at: {wt}"));
+        // Filesystem timestamps can trail the wall clock by a tick.
+        assert!(
+            (started.saturating_sub(2)..=finished).contains(&created_at),
+            "{created_at} not in {started}..={finished}"
+        );
+    }
+
+    let output = cargo_bin_cmd!("w")
+        .args(["-C", repo.to_str().unwrap(), "ls", "--preset", "full"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    for line in stdout.lines() {
+        let created = line.split('\t').nth(7).unwrap_or_default();
+        assert!(
+            created.len() == 20 && created.ends_with('Z') && created.as_bytes()[10] == b'T',
+            "expected an ISO 8601 creation time: {line}"
+        );
+    }
+}
+
+#[test]
+fn w_ls_raw_adds_porcelain_attributes() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = init_root_repo_with_feature_worktree(&tmp);
+    let feature = tmp.path().join("worktree_feature");
+    git(
+        &root.join("repo"),
+        &[
+            "worktree",
+            "lock",
+            "--reason",
+            "on a usb drive",
+            feature.to_str().unwrap(),
+        ],
+    );
+
+    let run = |extra: &[&str]| {
+        cargo_bin_cmd!("w")
+            .args(["ls", "--root", root.to_str().unwrap()])
+            .args(["--max-depth", "2"])
+            .args(extra)
+            .arg("--cache-path")
+            .arg(tmp.path().join("repo-index-cache.json"))
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&["--format", "json", "--raw"]);
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let worktrees = json["worktrees"].as_array().unwrap();
+    let feature = worktrees
+        .iter()
+        .find(|wt| wt["branch"] == "feature")
+        .unwrap();
+    assert_eq!(feature["raw"]["branch"], "refs/heads/feature");
+    assert_eq!(feature["raw"]["HEAD"], feature["head"]);
+    assert_eq!(feature["raw"]["locked"], "on a usb drive");
+    assert!(feature["raw"]["worktree"].is_string(), "{feature}");
+    // The typed fields are unchanged.
+    assert_eq!(feature["locked"], "on a usb drive");
+
+    let output = run(&["--format", "json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["worktrees"][0]["raw"].is_null(), "{json}");
+
+    let output = run(&["--raw"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--raw is only supported with --format json"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn w_ls_json_lists_warnings_and_quiet_silences_stderr() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = init_root_repo_with_feature_worktree(&tmp);
+    let cache_path = tmp.path().join("repo-index-cache.json");
+
+    let run = |extra: &[&str]| {
+        // An interrupted scan leaves an incomplete index, which the next run resumes.
+        std::fs::write(
+            &cache_path,
+            r#"{"schema_version": 1, "repos": [], "complete": false}"#,
+        )
+        .unwrap();
+        let output = cargo_bin_cmd!("w")
+            .args(["ls", "--root", root.to_str().unwrap()])
+            .args(["--max-depth", "2", "--format", "json"])
+            .arg("--cache-path")
+            .arg(&cache_path)
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        (json, String::from_utf8(output.stderr).unwrap())
+    };
+
+    let expected = serde_json::json!([{ "message": "resuming incomplete repo index scan" }]);
+    let (json, stderr) = run(&[]);
+    assert_eq!(json["warnings"], expected);
+    assert_eq!(json["worktrees"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        stderr,
+        "w ls: warning: resuming incomplete repo index scan\n"
+    );
+
+    let (json, stderr) = run(&["--quiet"]);
+    assert_eq!(json["warnings"], expected);
+    assert_eq!(stderr, "");
+
+    // A complete index has nothing to warn about.
+    let output = cargo_bin_cmd!("w")
+        .args(["ls", "--root", root.to_str().unwrap()])
+        .args(["--max-depth", "2", "--format", "json", "--cached"])
+        .arg("--cache-path")
+        .arg(&cache_path)
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["warnings"], serde_json::json!([]));
+}
+
+#[test]
+fn w_ls_json_keeps_every_key_unless_minimal() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = init_root_repo_with_feature_worktree(&tmp);
+
+    let run = |extra: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .args(["ls", "--root", root.to_str().unwrap()])
+            .args(["--max-depth", "2", "--format", "json"])
+            .args(extra)
+            .arg("--cache-path")
+            .arg(tmp.path().join("repo-index-cache.json"))
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+    let keys = |value: &serde_json::Value| {
+        value
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    // Enrichment flags fill in values without changing the key set or its order.
+    let plain = run(&[]);
+    let enriched = run(&["--sync", "--commit-count", "--repo-meta", "--raw"]);
+    assert_eq!(keys(&plain), keys(&enriched));
+    assert_eq!(
+        keys(&plain["worktrees"][0]),
+        keys(&enriched["worktrees"][0])
+    );
+    assert_eq!(keys(&plain)[..3], ["schema_version", "repos", "worktrees"]);
+    assert!(plain["repos"].is_null());
+    assert_eq!(plain["partial"], false);
+    for key in ["default_branch_ahead", "commit_count", "raw", "display"] {
+        assert!(plain["worktrees"][0][key].is_null(), "{key}");
+    }
+
+    let minimal = run(&["--minimal"]);
+    assert!(minimal.get("repos").is_none(), "{minimal}");
+    let worktree = &minimal["worktrees"][0];
+    for key in ["default_branch_ahead", "commit_count", "raw", "pr_url"] {
+        assert!(worktree.get(key).is_none(), "{key}: {worktree}");
+    }
+    assert_eq!(worktree["branch"], plain["worktrees"][0]["branch"]);
+}
+
+#[test]
+fn w_ls_include_meta_describes_the_effective_settings() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = init_root_repo_with_feature_worktree(&tmp);
+    let cache_path = tmp.path().join("repo-index-cache.json");
+
+    let run = |extra: &[&str]| {
+        cargo_bin_cmd!("w")
+            .env("XDG_CONFIG_HOME", tmp.path().join("config"))
+            .env_remove("W_MAX_CONCURRENT_REPOS")
+            .args(["ls", "--root", root.to_str().unwrap()])
+            .args(["--max-depth", "2", "--jobs", "3", "--cache-path"])
+            .arg(&cache_path)
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&[
+        "--format",
+        "json",
+        "--include-meta",
+        "--sync",
+        "--behind",
+        "main",
+        "--sort",
+        "path:desc",
+    ]);
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let meta = &json["meta"];
+    assert_eq!(meta["schema_version"], json["schema_version"]);
+    assert_eq!(meta["config_path"], serde_json::Value::Null);
+    assert_eq!(meta["roots"], serde_json::json!([root.to_str().unwrap()]));
+    assert_eq!(meta["max_depth"], 2);
+    assert_eq!(meta["jobs"], 3);
+    assert_eq!(meta["cache_path"], cache_path.to_str().unwrap());
+    assert_eq!(meta["cached"], false);
+    assert_eq!(meta["filters"]["behind"], "main");
+    assert_eq!(meta["filters"]["ahead_of"], serde_json::Value::Null);
+    assert_eq!(meta["enrichments"]["sync"], true);
+    assert_eq!(meta["enrichments"]["pr"], false);
+    assert_eq!(meta["sort"], serde_json::json!(["path:desc"]));
+    assert!(meta["w_version"].is_string(), "{meta}");
+
+    let output = run(&["--format", "json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json.get("meta").is_none(), "{json}");
+
+    let output = run(&["--include-meta"]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("--include-meta is only supported with --format json"),
+        "{output:?}"
+    );
+}
+
+#[test]
+fn w_ls_ndjson_streams_one_worktree_per_line_then_errors() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = init_root_repo_with_feature_worktree(&tmp);
+    let other = root.join("other");
+    std::fs::create_dir_all(&other).unwrap();
+    init_repo(&other);
+    let cache_path = tmp.path().join("repo-index-cache.json");
+
+    let run = |extra: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .env("XDG_CONFIG_HOME", tmp.path().join("config"))
+            .args(["ls", "--root", root.to_str().unwrap()])
+            .args(["--max-depth", "2", "--cache-path"])
+            .arg(&cache_path)
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w ls failed: {output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let json: LsOutput = serde_json::from_str(&run(&["--format", "json"])).unwrap();
+    let mut sorted_paths: Vec<String> = json.worktrees.into_iter().map(|wt| wt.path).collect();
+    sorted_paths.sort();
+    assert_eq!(sorted_paths.len(), 3);
+
+    for args in [&["--format", "ndjson"][..], &["--json-stream"][..]] {
+        let stdout = run(args);
+        let mut lines = stdout.lines().collect::<Vec<_>>();
+        let trailer: serde_json::Value = serde_json::from_str(lines.pop().unwrap()).unwrap();
+        assert_eq!(trailer["errors"], serde_json::json!([]), "{trailer}");
+        assert_eq!(trailer["partial"], false, "{trailer}");
+        assert!(trailer.get("worktrees").is_none(), "{trailer}");
+
+        // Repos come out in completion order, so only the set of worktrees is stable.
+        let mut paths = lines
+            .into_iter()
+            .map(|line| serde_json::from_str::<LsWorktree>(line).unwrap().path)
+            .collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(paths, sorted_paths);
+    }
+
+    let output = cargo_bin_cmd!("w")
+        .env("XDG_CONFIG_HOME", tmp.path().join("config"))
+        .args(["ls", "--root", root.to_str().unwrap()])
+        .args(["--max-depth", "2", "--cache-path"])
+        .arg(&cache_path)
+        .args(["--format", "ndjson", "--pretty"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success(), "{output:?}");
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("--pretty and --compact are only supported with --format json"),
+        "{output:?}"
+    );
+}
@@ -65,3 +65,589 @@ fn w_new_creates_then_switches() {
     let path2 = parse_path(&output2.stdout);
     assert_eq!(path2, path1);
 }
+
+#[test]
+fn w_new_reports_created_vs_existing() {
+    let tmp = tempfile::tempdir().unwrap();
+    init_repo(tmp.path());
+
+    let run_new = || {
+        cargo_bin_cmd!("w")
+            .current_dir(tmp.path())
+            .env(
+                "WORKTRUNK_WORKTREE_PATH",
+                ".worktrees/{{ branch | sanitize }}",
+            )
+            .args(["new", "feature", "--verbose", "--existing-exit-code", "3"])
+            .output()
+            .unwrap()
+    };
+
+    let created = run_new();
+    assert!(created.status.success(), "w new failed: {created:?}");
+    let path = parse_path(&created.stdout);
+    let stderr = String::from_utf8_lossy(&created.stderr);
+    assert!(
+        stderr.contains(&format!("w: created worktree: {}", path.display())),
+        "{stderr}"
+    );
+
+    let existing = run_new();
+    assert_eq!(existing.status.code(), Some(3), "{existing:?}");
+    assert_eq!(parse_path(&existing.stdout), path);
+    let stderr = String::from_utf8_lossy(&existing.stderr);
+    assert!(
+        stderr.contains(&format!("w: existing worktree: {}", path.display())),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn w_new_reads_branch_names_from_stdin() {
+    let tmp = tempfile::tempdir().unwrap();
+    init_repo(tmp.path());
+
+    let run_new = |stdin: &str| {
+        cargo_bin_cmd!("w")
+            .current_dir(tmp.path())
+            .env(
+                "WORKTRUNK_WORKTREE_PATH",
+                ".worktrees/{{ branch | sanitize }}",
+            )
+            .args(["new", "@-"])
+            .write_stdin(stdin)
+            .output()
+            .unwrap()
+    };
+
+    let output = run_new("  fix-login \n\nfix-logout\n");
+    assert!(output.status.success(), "w new failed: {output:?}");
+    let last = parse_path(&output.stdout);
+    assert!(last.ends_with("fix-logout"), "{last:?}");
+    let first = last.with_file_name("fix-login");
+    assert!(first.is_dir(), "{first:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&format!("w: created worktree: {}", first.display())),
+        "{stderr}"
+    );
+
+    let output = run_new(" \n");
+    assert!(!output.status.success(), "{output:?}");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("no branch name in stdin"),
+        "{output:?}"
+    );
+}
+
+fn write_post_create_hook(config_home: &Path, command: &str) {
+    write_hooks_config(config_home, &[command], None);
+}
+
+fn write_hooks_config(config_home: &Path, commands: &[&str], on_failure: Option<&str>) {
+    let config_dir = config_home.join("w");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    let mut config = format!("[hooks]\npost_create = {commands:?}\n");
+    if let Some(on_failure) = on_failure {
+        config.push_str(&format!("on_failure = {on_failure:?}\n"));
+    }
+    std::fs::write(config_dir.join("config.toml"), config).unwrap();
+}
+
+#[test]
+fn w_new_runs_post_create_hooks_once() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    let config_home = tmp.path().join("config");
+    write_post_create_hook(&config_home, "echo \"$W_BRANCH\" >> hook.log");
+
+    let run_new = || {
+        cargo_bin_cmd!("w")
+            .current_dir(&repo)
+            .env("XDG_CONFIG_HOME", &config_home)
+            .env(
+                "WORKTRUNK_WORKTREE_PATH",
+                ".worktrees/{{ branch | sanitize }}",
+            )
+            .args(["new", "feature"])
+            .output()
+            .unwrap()
+    };
+
+    let output = run_new();
+    assert!(output.status.success(), "w new failed: {output:?}");
+    let path = parse_path(&output.stdout);
+    assert_eq!(
+        std::fs::read_to_string(path.join("hook.log")).unwrap(),
+        "feature\n"
+    );
+
+    // Switching to an existing worktree doesn't re-run hooks.
+    let output = run_new();
+    assert!(output.status.success(), "w new failed: {output:?}");
+    assert_eq!(parse_path(&output.stdout), path);
+    assert_eq!(
+        std::fs::read_to_string(path.join("hook.log")).unwrap(),
+        "feature\n"
+    );
+}
+
+#[test]
+fn w_new_runs_project_hooks_after_global_hooks() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    let config_home = tmp.path().join("config");
+    write_post_create_hook(&config_home, "echo global >> hook.log");
+    let config_path = config_home.join("w").join("config.toml");
+    let global = std::fs::read_to_string(&config_path).unwrap();
+    std::fs::write(
+        &config_path,
+        format!(
+            "trusted_projects = [{:?}]\n{global}",
+            repo.display().to_string()
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        repo.join(".w.toml"),
+        "[hooks]\npost_create = [\"echo project >> hook.log\"]\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(&repo)
+        .env("XDG_CONFIG_HOME", &config_home)
+        .env(
+            "WORKTRUNK_WORKTREE_PATH",
+            ".worktrees/{{ branch | sanitize }}",
+        )
+        .args(["new", "feature"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w new failed: {output:?}");
+    let path = parse_path(&output.stdout);
+    assert_eq!(
+        std::fs::read_to_string(path.join("hook.log")).unwrap(),
+        "global\nproject\n"
+    );
+}
+
+#[test]
+fn w_new_skips_hooks_from_an_untrusted_project() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    let config_home = tmp.path().join("config");
+    write_post_create_hook(&config_home, "echo global >> hook.log");
+    std::fs::write(
+        repo.join(".w.toml"),
+        "[hooks]\npost_create = [\"echo project >> hook.log\"]\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(&repo)
+        .env("XDG_CONFIG_HOME", &config_home)
+        .env(
+            "WORKTRUNK_WORKTREE_PATH",
+            ".worktrees/{{ branch | sanitize }}",
+        )
+        .args(["new", "feature"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w new failed: {output:?}");
+    let path = parse_path(&output.stdout);
+    assert_eq!(
+        std::fs::read_to_string(path.join("hook.log")).unwrap(),
+        "global\n"
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("w new: warning: skipping untrusted post_create hook from ")
+            && stderr.contains("echo project >> hook.log"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn w_new_no_hooks_skips_post_create_hooks() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    let config_home = tmp.path().join("config");
+    write_post_create_hook(&config_home, "touch hook-ran");
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(&repo)
+        .env("XDG_CONFIG_HOME", &config_home)
+        .env(
+            "WORKTRUNK_WORKTREE_PATH",
+            ".worktrees/{{ branch | sanitize }}",
+        )
+        .args(["new", "feature", "--no-hooks"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w new failed: {output:?}");
+    let path = parse_path(&output.stdout);
+    assert!(path.exists());
+    assert!(!path.join("hook-ran").exists());
+}
+
+fn run_new_with_failing_hook(
+    on_failure: Option<&str>,
+    extra_args: &[&str],
+) -> (tempfile::TempDir, std::process::Output) {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    let config_home = tmp.path().join("config");
+    write_hooks_config(
+        &config_home,
+        &[
+            "echo first >> hook.log",
+            "echo mock hook failing >&2; exit 3",
+            "echo third >> hook.log",
+        ],
+        on_failure,
+    );
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(&repo)
+        .env("XDG_CONFIG_HOME", &config_home)
+        .env(
+            "WORKTRUNK_WORKTREE_PATH",
+            ".worktrees/{{ branch | sanitize }}",
+        )
+        .args(["new", "feature"])
+        .args(extra_args)
+        .output()
+        .unwrap();
+    (tmp, output)
+}
+
+#[test]
+fn w_new_hook_failure_aborts_by_default() {
+    let (tmp, output) = run_new_with_failing_hook(None, &[]);
+    assert!(!output.status.success(), "w new should fail: {output:?}");
+    assert!(output.stdout.is_empty());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("mock hook failing"), "stderr: {stderr}");
+    assert!(
+        stderr.contains("post_create hook failed"),
+        "stderr: {stderr}"
+    );
+
+    // The worktree stays, but later hooks don't run.
+    let worktree = tmp.path().join("repo/.worktrees/feature");
+    assert_eq!(
+        std::fs::read_to_string(worktree.join("hook.log")).unwrap(),
+        "first\n"
+    );
+}
+
+#[test]
+fn w_new_rollback_on_hook_failure_removes_the_new_worktree_and_branch() {
+    let (tmp, output) = run_new_with_failing_hook(None, &["--rollback-on-hook-failure"]);
+    assert!(!output.status.success(), "w new should fail: {output:?}");
+    assert!(output.stdout.is_empty());
+
+    let worktree = tmp.path().join("repo/.worktrees/feature");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("post_create hook failed"),
+        "stderr: {stderr}"
+    );
+    assert!(
+        stderr.contains("w: rolled back worktree after hook failure: "),
+        "stderr: {stderr}"
+    );
+    assert!(!worktree.exists());
+
+    let branches = std::process::Command::new("git")
+        .args(["branch", "--list", "feature"])
+        .current_dir(tmp.path().join("repo"))
+        .output()
+        .unwrap();
+    assert!(branches.stdout.is_empty(), "{branches:?}");
+}
+
+#[test]
+fn w_new_hook_failure_warn_runs_remaining_hooks() {
+    let (_tmp, output) = run_new_with_failing_hook(Some("warn"), &[]);
+    assert!(output.status.success(), "w new failed: {output:?}");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("warning: post_create hook failed"),
+        "stderr: {stderr}"
+    );
+    assert!(
+        stderr.contains("1 of 3 post_create hooks failed"),
+        "stderr: {stderr}"
+    );
+
+    let path = parse_path(&output.stdout);
+    assert_eq!(
+        std::fs::read_to_string(path.join("hook.log")).unwrap(),
+        "first\nthird\n"
+    );
+}
+
+#[test]
+fn w_new_hook_failure_continue_summarizes_at_end() {
+    let (_tmp, output) = run_new_with_failing_hook(Some("continue"), &[]);
+    assert!(output.status.success(), "w new failed: {output:?}");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("warning:"), "stderr: {stderr}");
+    assert!(
+        stderr.contains("1 of 3 post_create hooks failed"),
+        "stderr: {stderr}"
+    );
+
+    let path = parse_path(&output.stdout);
+    assert_eq!(
+        std::fs::read_to_string(path.join("hook.log")).unwrap(),
+        "first\nthird\n"
+    );
+}
+
+fn git_stdout(current_dir: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(current_dir)
+        .output()
+        .unwrap_or_else(|e| panic!("failed to run git {args:?}: {e}"));
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+#[test]
+fn w_new_detach_creates_detached_worktree_named_by_short_sha() {
+    let tmp = tempfile::tempdir().unwrap();
+    init_repo(tmp.path());
+    let first = git_stdout(tmp.path(), &["rev-parse", "HEAD"]);
+    let short = git_stdout(tmp.path(), &["rev-parse", "--short", "HEAD"]);
+    std::fs::write(tmp.path().join("later.txt"), "later\n").unwrap();
+    git(tmp.path(), &["add", "later.txt"]);
+    git(tmp.path(), &["commit", "-m", "later"]);
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(tmp.path())
+        .env(
+            "WORKTRUNK_WORKTREE_PATH",
+            ".worktrees/{{ branch | sanitize }}",
+        )
+        .args(["new", "--detach", &first])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w new --detach failed: {output:?}");
+
+    let path = parse_path(&output.stdout);
+    assert_eq!(path.file_name().unwrap().to_str().unwrap(), short);
+    assert_eq!(git_stdout(&path, &["rev-parse", "HEAD"]), first);
+    assert_eq!(git_stdout(&path, &["branch", "--show-current"]), "");
+    assert!(!path.join("later.txt").exists());
+}
+
+#[test]
+fn w_new_detach_with_base_uses_positional_name() {
+    let tmp = tempfile::tempdir().unwrap();
+    init_repo(tmp.path());
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(tmp.path())
+        .env(
+            "WORKTRUNK_WORKTREE_PATH",
+            ".worktrees/{{ branch | sanitize }}",
+        )
+        .args(["new", "old-rev", "--detach", "--base", "HEAD"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w new --detach failed: {output:?}");
+
+    let path = parse_path(&output.stdout);
+    assert_eq!(path.file_name().unwrap(), "old-rev");
+    assert_eq!(git_stdout(&path, &["branch", "--show-current"]), "");
+    assert_eq!(
+        git_stdout(tmp.path(), &["branch", "--list", "old-rev"]),
+        "",
+        "--detach must not create a branch"
+    );
+}
+
+#[test]
+fn w_new_track_sets_the_branch_upstream() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    git(&repo, &["remote", "add", "origin", repo.to_str().unwrap()]);
+    git(&repo, &["fetch", "origin"]);
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(&repo)
+        .env(
+            "WORKTRUNK_WORKTREE_PATH",
+            ".worktrees/{{ branch | sanitize }}",
+        )
+        .args(["new", "feature", "--track", "origin/main"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w new failed: {output:?}");
+    assert!(parse_path(&output.stdout).exists());
+
+    assert_eq!(
+        git_stdout(&repo, &["rev-parse", "--abbrev-ref", "feature@{upstream}"]),
+        "origin/main"
+    );
+
+    // An upstream that doesn't exist fails, without leaving the worktree or branch behind.
+    let output = cargo_bin_cmd!("w")
+        .current_dir(&repo)
+        .env(
+            "WORKTRUNK_WORKTREE_PATH",
+            ".worktrees/{{ branch | sanitize }}",
+        )
+        .args(["new", "other", "--track", "origin/missing"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success(), "w new succeeded: {output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("w: rolled back worktree after --track failure: "),
+        "{stderr}"
+    );
+    assert!(!repo.join(".worktrees/other").exists());
+    assert_eq!(git_stdout(&repo, &["branch", "--list", "other"]), "");
+
+    assert!(
+        !cargo_bin_cmd!("w")
+            .current_dir(&repo)
+            .args(["new", "feature", "--track", "origin/main", "--detach"])
+            .output()
+            .unwrap()
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn w_new_base_upstream_resolves_the_current_branch_upstream() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    let new = |args: &[&str]| {
+        cargo_bin_cmd!("w")
+            .current_dir(&repo)
+            .env(
+                "WORKTRUNK_WORKTREE_PATH",
+                ".worktrees/{{ branch | sanitize }}",
+            )
+            .arg("new")
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    let output = new(&["feature", "--base", "@{upstream}"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("has no upstream"), "{stderr}");
+
+    // Give main an upstream that's a commit behind it.
+    git(&repo, &["remote", "add", "origin", repo.to_str().unwrap()]);
+    git(&repo, &["fetch", "origin"]);
+    git(&repo, &["branch", "--set-upstream-to=origin/main", "main"]);
+    std::fs::write(repo.join("README.md"), "ahead\n").unwrap();
+    git(&repo, &["commit", "-am", "ahead of origin"]);
+
+    for (branch, base) in [("feature", "@{upstream}"), ("other", "@{u}")] {
+        let output = new(&[branch, "--base", base]);
+        assert!(output.status.success(), "w new failed: {output:?}");
+        assert!(parse_path(&output.stdout).exists());
+        assert_eq!(
+            git_stdout(&repo, &["rev-parse", branch]),
+            git_stdout(&repo, &["rev-parse", "origin/main"])
+        );
+    }
+}
+
+#[test]
+fn w_new_base_fetches_and_prefers_remote_branches() {
+    let tmp = tempfile::tempdir().unwrap();
+    let upstream = tmp.path().join("upstream");
+    std::fs::create_dir_all(&upstream).unwrap();
+    init_repo(&upstream);
+    let repo = tmp.path().join("repo");
+    git(
+        tmp.path(),
+        &["clone", upstream.to_str().unwrap(), repo.to_str().unwrap()],
+    );
+    // Pushed after the clone, so `origin/release-2.0` hasn't been fetched yet.
+    git(&upstream, &["switch", "-c", "release-2.0"]);
+    std::fs::write(upstream.join("README.md"), "release\n").unwrap();
+    git(&upstream, &["commit", "-am", "release"]);
+    let release = git_stdout(&upstream, &["rev-parse", "HEAD"]);
+    // A local branch with the same name as the remote one, on another commit.
+    git(&repo, &["branch", "origin/release-2.0"]);
+
+    let new = |args: &[&str]| {
+        cargo_bin_cmd!("w")
+            .current_dir(&repo)
+            .env(
+                "WORKTRUNK_WORKTREE_PATH",
+                ".worktrees/{{ branch | sanitize }}",
+            )
+            .arg("new")
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let upstream_of = |branch: &str| {
+        git_stdout(
+            &repo,
+            &[
+                "rev-parse",
+                "--abbrev-ref",
+                &format!("{branch}@{{upstream}}"),
+            ],
+        )
+    };
+
+    // Same name as the remote branch: it's tracked.
+    let output = new(&["release-2.0", "--base", "origin/release-2.0"]);
+    assert!(output.status.success(), "w new failed: {output:?}");
+    assert!(parse_path(&output.stdout).exists());
+    assert_eq!(git_stdout(&repo, &["rev-parse", "release-2.0"]), release);
+    assert_eq!(upstream_of("release-2.0"), "origin/release-2.0");
+
+    // Another name: created from the remote branch, not the local look-alike, and not
+    // tracking it unless asked.
+    let output = new(&["feature", "--base", "origin/release-2.0"]);
+    assert!(output.status.success(), "w new failed: {output:?}");
+    assert_eq!(git_stdout(&repo, &["rev-parse", "feature"]), release);
+    assert_eq!(upstream_of("feature"), "");
+    let output = new(&[
+        "hotfix",
+        "--base",
+        "origin/release-2.0",
+        "--track",
+        "origin/release-2.0",
+    ]);
+    assert!(output.status.success(), "w new failed: {output:?}");
+    assert_eq!(upstream_of("hotfix"), "origin/release-2.0");
+
+    let output = new(&["other", "--base", "upstream/main"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("no remote named upstream"), "{stderr}");
+}
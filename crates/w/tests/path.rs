@@ -0,0 +1,140 @@
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use dunce::canonicalize;
+
+fn git(current_dir: &Path, args: &[&str]) {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(current_dir)
+        .output()
+        .unwrap_or_else(|e| panic!("failed to run git {args:?}: {e}"));
+
+    if !output.status.success() {
+        panic!(
+            "git {args:?} failed\nstdout:\n{}\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+}
+
+fn init_repo(repo_dir: &Path) {
+    git(repo_dir, &["init", "-b", "main"]);
+    git(repo_dir, &["config", "user.name", "Test User"]);
+    git(repo_dir, &["config", "user.email", "test@example.com"]);
+
+    std::fs::write(repo_dir.join("README.md"), "hello\n").unwrap();
+    git(repo_dir, &["add", "README.md"]);
+    git(repo_dir, &["commit", "-m", "initial"]);
+}
+
+#[test]
+fn w_path_prints_existing_worktree_without_creating() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    let feature = tmp.path().join("worktree_feature");
+    git(
+        &repo,
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "feature",
+            feature.to_str().unwrap(),
+        ],
+    );
+    git(&repo, &["branch", "no-worktree"]);
+
+    let path = |args: &[&str]| {
+        cargo_bin_cmd!("w")
+            .env("XDG_CONFIG_HOME", tmp.path().join("config"))
+            .env(
+                "WORKTRUNK_WORKTREE_PATH",
+                ".worktrees/{{ branch | sanitize }}",
+            )
+            .args(["-C", repo.to_str().unwrap(), "path"])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let stdout_path = |output: std::process::Output| {
+        assert!(output.status.success(), "w path failed: {output:?}");
+        canonicalize(String::from_utf8(output.stdout).unwrap().trim()).unwrap()
+    };
+
+    assert_eq!(
+        stdout_path(path(&["feature"])),
+        canonicalize(&feature).unwrap()
+    );
+    // Symbols resolve like other commands: `^` is the default branch.
+    assert_eq!(stdout_path(path(&["^"])), canonicalize(&repo).unwrap());
+
+    let output = path(&["no-worktree"]);
+    assert!(!output.status.success(), "expected failure: {output:?}");
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("no worktree exists for branch no-worktree"),
+        "{output:?}"
+    );
+    assert!(!repo.join(".worktrees").exists());
+
+    let created = stdout_path(path(&["no-worktree", "--create"]));
+    assert_eq!(
+        created,
+        canonicalize(repo.join(".worktrees/no-worktree")).unwrap()
+    );
+    assert_eq!(stdout_path(path(&["no-worktree"])), created);
+}
+
+#[test]
+fn w_path_for_previews_the_templated_path_without_creating_anything() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+
+    let path_for = |template: &str, branch: &str| {
+        cargo_bin_cmd!("w")
+            .env("XDG_CONFIG_HOME", tmp.path().join("config"))
+            .env("WORKTRUNK_WORKTREE_PATH", template)
+            .args(["-C", repo.to_str().unwrap(), "path-for", branch])
+            .output()
+            .unwrap()
+    };
+
+    let output = path_for(".worktrees/{{ branch | sanitize }}", "feature/new-thing");
+    assert!(output.status.success(), "w path-for failed: {output:?}");
+    assert_eq!(
+        Path::new(String::from_utf8(output.stdout).unwrap().trim()),
+        canonicalize(&repo)
+            .unwrap()
+            .join(".worktrees/feature-new-thing")
+    );
+    assert!(!repo.join(".worktrees").exists());
+    // The branch wasn't created either.
+    let output = std::process::Command::new("git")
+        .args(["branch", "--list", "feature/new-thing"])
+        .current_dir(&repo)
+        .output()
+        .unwrap();
+    assert!(output.stdout.is_empty(), "{output:?}");
+
+    // The default branch lives in the repo itself, and `^` resolves to it.
+    let output = path_for(".worktrees/{{ branch | sanitize }}", "^");
+    assert!(output.status.success(), "w path-for failed: {output:?}");
+    assert_eq!(
+        canonicalize(String::from_utf8(output.stdout).unwrap().trim()).unwrap(),
+        canonicalize(&repo).unwrap()
+    );
+
+    let output = path_for(".worktrees/{{ branch | no_such_filter }}", "feature");
+    assert!(!output.status.success(), "expected failure: {output:?}");
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("failed to compute the worktree path for feature"),
+        "{output:?}"
+    );
+}
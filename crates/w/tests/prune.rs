@@ -87,3 +87,365 @@ fn w_prune_removes_stale_worktree_dirs() {
     assert!(feature_path.exists(), "expected feature worktree to remain");
     assert!(!stale_dir.exists(), "expected stale dir to be removed");
 }
+
+#[test]
+fn w_prune_skips_stale_dirs_holding_another_repos_worktree() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    let other = tmp.path().join("other");
+    std::fs::create_dir_all(&repo).unwrap();
+    std::fs::create_dir_all(&other).unwrap();
+    init_repo(&repo);
+    init_repo(&other);
+
+    let stale_dir = repo.join(".worktrees/stale");
+    std::fs::create_dir_all(&stale_dir).unwrap();
+    let gitdir = git_common_dir(&repo).join("worktrees/stale");
+    std::fs::write(
+        stale_dir.join(".git"),
+        format!("gitdir: {}\n", gitdir.display()),
+    )
+    .unwrap();
+    let nested = stale_dir.join("vendored/other-feature");
+    git(
+        &other,
+        &["worktree", "add", "-b", "feature", nested.to_str().unwrap()],
+    );
+
+    let prune = |extra_args: &[&str]| {
+        cargo_bin_cmd!("w")
+            .current_dir(&repo)
+            .env(
+                "WORKTRUNK_WORKTREE_PATH",
+                ".worktrees/{{ branch | sanitize }}",
+            )
+            .arg("prune")
+            .args(extra_args)
+            .output()
+            .unwrap()
+    };
+
+    let output = prune(&[]);
+    assert!(output.status.success(), "w prune failed: {output:?}");
+    assert!(output.stdout.is_empty(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let other_root = dunce::canonicalize(&other).unwrap();
+    assert!(
+        stderr.contains(&format!("contains a worktree of {}", other_root.display()))
+            && stderr.contains("skipping (use --force)"),
+        "{stderr}"
+    );
+    assert!(nested.join("README.md").exists());
+
+    let output = prune(&["--force"]);
+    assert!(
+        output.status.success(),
+        "w prune --force failed: {output:?}"
+    );
+    assert_eq!(parse_path(&output.stdout), stale_dir);
+    assert!(!stale_dir.exists());
+}
+
+#[test]
+fn w_prune_repos_dry_run_json_reports_candidates_without_deleting() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path().join("root");
+    let mut stale_dirs = Vec::new();
+    for name in ["repo_a", "repo_b"] {
+        let repo = root.join(name);
+        std::fs::create_dir_all(&repo).unwrap();
+        init_repo(&repo);
+        let stale_dir = repo.join(".worktrees/stale");
+        std::fs::create_dir_all(&stale_dir).unwrap();
+        let gitdir = git_common_dir(&repo).join("worktrees/stale");
+        std::fs::write(
+            stale_dir.join(".git"),
+            format!("gitdir: {}\n", gitdir.display()),
+        )
+        .unwrap();
+        stale_dirs.push((dunce::canonicalize(&repo).unwrap(), stale_dir));
+    }
+
+    let prune = |extra_args: &[&str]| {
+        cargo_bin_cmd!("w")
+            .current_dir(tmp.path())
+            .env(
+                "WORKTRUNK_WORKTREE_PATH",
+                ".worktrees/{{ branch | sanitize }}",
+            )
+            .args(["prune", "--repos", "--root", root.to_str().unwrap()])
+            .arg("--cache-path")
+            .arg(tmp.path().join("repo-index-cache.json"))
+            .args(extra_args)
+            .output()
+            .unwrap()
+    };
+
+    let output = prune(&["--json"]);
+    assert!(!output.status.success(), "expected failure: {output:?}");
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("the following required arguments were not provided:\n  --dry-run"),
+        "{output:?}"
+    );
+
+    let output = prune(&["--dry-run", "--json"]);
+    assert!(output.status.success(), "w prune failed: {output:?}");
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["schema_version"], 1);
+    assert_eq!(report["errors"], serde_json::json!([]));
+    let mut candidates = report["candidates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| {
+            assert_eq!(c["reason"], "orphaned-gitdir");
+            assert_eq!(c["removable"], true);
+            assert_eq!(c["nested_worktrees"], serde_json::json!([]));
+            (
+                PathBuf::from(c["repo_path"].as_str().unwrap()),
+                PathBuf::from(c["path"].as_str().unwrap()),
+            )
+        })
+        .collect::<Vec<_>>();
+    candidates.sort();
+    assert_eq!(candidates, stale_dirs);
+    assert!(stale_dirs.iter().all(|(_, dir)| dir.exists()));
+
+    let output = prune(&[]);
+    assert!(output.status.success(), "w prune failed: {output:?}");
+    assert!(stale_dirs.iter().all(|(_, dir)| !dir.exists()));
+}
+
+// Opening a directory to set its mtime doesn't work on Windows.
+#[cfg(unix)]
+#[test]
+fn w_prune_keep_spares_the_most_recent_stale_dirs() {
+    let tmp = tempfile::tempdir().unwrap();
+    init_repo(tmp.path());
+
+    // Oldest first: `c` was touched most recently.
+    let now = std::time::SystemTime::now();
+    let mut stale_dirs = Vec::new();
+    for (name, age_hours) in [("a", 3), ("b", 2), ("c", 1)] {
+        let stale_dir = tmp.path().join(".worktrees").join(name);
+        std::fs::create_dir_all(&stale_dir).unwrap();
+        let gitdir = git_common_dir(tmp.path()).join("worktrees").join(name);
+        std::fs::write(
+            stale_dir.join(".git"),
+            format!("gitdir: {}\n", gitdir.display()),
+        )
+        .unwrap();
+        std::fs::File::open(&stale_dir)
+            .unwrap()
+            .set_modified(now - std::time::Duration::from_secs(age_hours * 3600))
+            .unwrap();
+        stale_dirs.push(stale_dir);
+    }
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(tmp.path())
+        .env(
+            "WORKTRUNK_WORKTREE_PATH",
+            ".worktrees/{{ branch | sanitize }}",
+        )
+        .args(["prune", "--keep", "2"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w prune failed: {output:?}");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stale_dirs[0].exists(), "expected the oldest to be removed");
+    assert!(stdout.trim().ends_with("/.worktrees/a"), "{stdout}");
+    for kept in &stale_dirs[1..] {
+        assert!(kept.exists(), "expected {} to be kept", kept.display());
+        assert!(
+            stderr.contains(&format!(
+                "w prune: keeping {} (one of the 2 most recent)",
+                dunce::canonicalize(kept).unwrap().display()
+            )),
+            "{stderr}"
+        );
+    }
+}
+
+#[test]
+fn w_prune_skips_moved_worktrees_git_has_locked_unless_include_locked() {
+    let tmp = tempfile::tempdir().unwrap();
+    init_repo(tmp.path());
+    let original = tmp.path().join(".worktrees/feature");
+    git(
+        tmp.path(),
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "feature",
+            original.to_str().unwrap(),
+        ],
+    );
+    git(
+        tmp.path(),
+        &[
+            "worktree",
+            "lock",
+            "--reason",
+            "on usb",
+            original.to_str().unwrap(),
+        ],
+    );
+    // Moved by hand: git still has (and, being locked, keeps) the metadata for the old path.
+    let moved = tmp.path().join(".worktrees/moved");
+    std::fs::rename(&original, &moved).unwrap();
+
+    let prune = |args: &[&str]| {
+        let output = cargo_bin_cmd!("w")
+            .current_dir(tmp.path())
+            .env(
+                "WORKTRUNK_WORKTREE_PATH",
+                ".worktrees/{{ branch | sanitize }}",
+            )
+            .arg("prune")
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w prune failed: {output:?}");
+        output
+    };
+
+    let output = prune(&[]);
+    assert!(moved.exists(), "expected the locked worktree to be kept");
+    assert!(output.stdout.is_empty(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&format!(
+            "skipping {}: its worktree is locked (on usb) (use --include-locked)",
+            dunce::canonicalize(&moved).unwrap().display()
+        )),
+        "{stderr}"
+    );
+
+    let output = prune(&["--include-locked", "--dry-run", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let candidate = &json["candidates"][0];
+    assert_eq!(candidate["reason"], "moved-worktree", "{json}");
+    assert_eq!(candidate["locked"], "on usb", "{json}");
+    assert_eq!(candidate["removable"], true, "{json}");
+
+    // Uncommitted work in the moved checkout keeps it, short of --force.
+    std::fs::write(moved.join("wip.txt"), "wip\n").unwrap();
+    let output = prune(&["--include-locked"]);
+    assert!(moved.exists(), "expected the dirty worktree to be kept");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("it has uncommitted changes"), "{stderr}");
+
+    prune(&["--include-locked", "--force"]);
+    assert!(!moved.exists(), "expected --include-locked to remove it");
+    // Unlocked on the way, so git can drop the metadata of the vanished worktree.
+    let list = String::from_utf8(git(tmp.path(), &["worktree", "list", "--porcelain"])).unwrap();
+    assert!(!list.contains("locked"), "{list}");
+    git(tmp.path(), &["worktree", "prune"]);
+    let list = String::from_utf8(git(tmp.path(), &["worktree", "list", "--porcelain"])).unwrap();
+    assert!(!list.contains("feature"), "{list}");
+}
+
+// Reaches one repo through a symlink, which needs unix.
+#[cfg(unix)]
+#[test]
+fn w_prune_and_ls_attribute_worktrees_in_a_shared_root_by_their_gitdir() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path().join("root");
+    let repo_a = root.join("repo_a");
+    let repo_b = root.join("repo_b");
+    for repo in [&repo_a, &repo_b] {
+        std::fs::create_dir_all(repo).unwrap();
+        init_repo(repo);
+    }
+    // `../wt/...` gives both repos the same worktree root.
+    let shared = root.join("wt");
+
+    // repo_b's live worktree, sitting where repo_a's could be.
+    let b_live = shared.join("b-live");
+    git(
+        &repo_b,
+        &["worktree", "add", "-b", "b-live", b_live.to_str().unwrap()],
+    );
+
+    let write_pointer = |dir: &Path, gitdir: &Path| {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join(".git"), format!("gitdir: {}\n", gitdir.display())).unwrap();
+    };
+    // repo_a has never had a worktree, so its `.git/worktrees` doesn't exist; the
+    // pointer reaches it through a symlink.
+    let alias = tmp.path().join("alias");
+    std::os::unix::fs::symlink(&root, &alias).unwrap();
+    let a_stale = shared.join("a-stale");
+    write_pointer(&a_stale, &alias.join("repo_a/.git/worktrees/a-stale"));
+    let b_stale = shared.join("b-stale");
+    write_pointer(&b_stale, &git_common_dir(&repo_b).join("worktrees/b-stale"));
+    let junk = shared.join("junk");
+    std::fs::create_dir_all(&junk).unwrap();
+    std::fs::write(junk.join(".git"), "not a gitdir pointer\n").unwrap();
+
+    let w = |args: &[&str]| {
+        cargo_bin_cmd!("w")
+            .current_dir(tmp.path())
+            .env("WORKTRUNK_WORKTREE_PATH", "../wt/{{ branch | sanitize }}")
+            .args(args)
+            .args(["--root", root.to_str().unwrap()])
+            .arg("--cache-path")
+            .arg(tmp.path().join("repo-index-cache.json"))
+            .output()
+            .unwrap()
+    };
+
+    let output = w(&["prune", "--repos", "--dry-run", "--json"]);
+    assert!(output.status.success(), "w prune failed: {output:?}");
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["errors"], serde_json::json!([]), "{report}");
+    let mut candidates = report["candidates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| {
+            (
+                PathBuf::from(c["repo_path"].as_str().unwrap()),
+                PathBuf::from(c["path"].as_str().unwrap()),
+            )
+        })
+        .collect::<Vec<_>>();
+    candidates.sort();
+    let repo_a = dunce::canonicalize(&repo_a).unwrap();
+    let repo_b = dunce::canonicalize(&repo_b).unwrap();
+    let shared = dunce::canonicalize(&shared).unwrap();
+    assert_eq!(
+        candidates,
+        vec![
+            (repo_a.clone(), shared.join("a-stale")),
+            (repo_b.clone(), shared.join("b-stale")),
+        ]
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&format!("skipping {}", shared.join("junk").display())),
+        "{stderr}"
+    );
+
+    let output = w(&["ls", "--format", "json"]);
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let b_live_entry = listing["worktrees"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|wt| wt["path"] == shared.join("b-live").to_str().unwrap())
+        .unwrap_or_else(|| panic!("b-live not listed: {listing}"));
+    assert_eq!(b_live_entry["repo_path"], repo_b.to_str().unwrap());
+
+    let output = w(&["prune", "--repos"]);
+    assert!(output.status.success(), "w prune failed: {output:?}");
+    assert!(!a_stale.exists() && !b_stale.exists());
+    assert!(b_live.join("README.md").exists());
+    assert!(junk.exists());
+}
@@ -191,3 +191,380 @@ fn w_repo_pick_filter_uses_cache() {
 
     assert_eq!(selected, canonicalize(&repo_b).unwrap());
 }
+
+#[test]
+fn w_repo_validate_reports_and_prunes_invalid_entries() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let root = tmp.path().join("root");
+    let repo_a = root.join("repo_a");
+    let repo_b = root.join("repo_b");
+    std::fs::create_dir_all(&repo_a).unwrap();
+    std::fs::create_dir_all(&repo_b).unwrap();
+    init_repo(&repo_a);
+    init_repo(&repo_b);
+
+    let cache_path = tmp.path().join("repo-index-cache.json");
+
+    let output = cargo_bin_cmd!("w")
+        .args([
+            "repo",
+            "index",
+            "--root",
+            root.to_str().unwrap(),
+            "--max-depth",
+            "2",
+            "--cache-path",
+            cache_path.to_str().unwrap(),
+            "--validate",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w repo index failed: {output:?}");
+
+    let expected_a = canonicalize(&repo_a).unwrap().to_string_lossy().to_string();
+    let expected_b = canonicalize(&repo_b).unwrap().to_string_lossy().to_string();
+    std::fs::remove_dir_all(&repo_b).unwrap();
+
+    let output = cargo_bin_cmd!("w")
+        .args([
+            "repo",
+            "validate",
+            "--cache-path",
+            cache_path.to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+    assert!(
+        !output.status.success(),
+        "expected failure, got: {output:?}"
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let invalid = stdout
+        .lines()
+        .map(|line| line.split('\t').next().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(invalid, [expected_b.as_str()]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("1 valid, 1 invalid"),
+        "stderr did not summarize counts:\n{stderr}"
+    );
+
+    let output = cargo_bin_cmd!("w")
+        .args([
+            "repo",
+            "index",
+            "--cached",
+            "--cache-path",
+            cache_path.to_str().unwrap(),
+            "--validate",
+            "--prune-invalid",
+        ])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "w repo index --prune-invalid failed: {output:?}"
+    );
+
+    let index: IndexOutput = serde_json::from_slice(&output.stdout).unwrap();
+    let paths = index.repos.into_iter().map(|r| r.path).collect::<Vec<_>>();
+    assert_eq!(paths, std::slice::from_ref(&expected_a));
+
+    let cached: IndexOutput =
+        serde_json::from_str(&std::fs::read_to_string(&cache_path).unwrap()).unwrap();
+    let paths = cached.repos.into_iter().map(|r| r.path).collect::<Vec<_>>();
+    assert_eq!(paths, [expected_a]);
+}
+
+#[test]
+fn w_repo_index_resumes_partial_cache() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let root = tmp.path().join("root");
+    let repo_a = root.join("a/repo_a");
+    let repo_b = root.join("b/repo_b");
+    std::fs::create_dir_all(&repo_a).unwrap();
+    std::fs::create_dir_all(&repo_b).unwrap();
+    init_repo(&repo_a);
+    init_repo(&repo_b);
+
+    let config = tmp.path().join("w-config.toml");
+    std::fs::write(
+        &config,
+        format!("repo_roots = ['{}']\nmax_depth = 3\n", root.display()),
+    )
+    .unwrap();
+
+    // Not a repo itself, but discovery looks inside it (max_depth 3).
+    std::fs::create_dir_all(root.join("a/x")).unwrap();
+
+    // Simulate a scan interrupted after finishing subtree `a`, which recorded no repos.
+    // An unchanged mtime means the resumed scan trusts that record and skips `a`.
+    let subtree_a = canonicalize(root.join("a")).unwrap();
+    let mtime_ns = |dir: &Path| {
+        std::fs::metadata(dir)
+            .unwrap()
+            .modified()
+            .unwrap()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64
+    };
+    // The newest of the directories discovery looks at, not just `a` itself.
+    let subtree_mtime_ns = || {
+        [
+            subtree_a.clone(),
+            subtree_a.join("x"),
+            subtree_a.join("repo_a"),
+        ]
+        .into_iter()
+        .map(|dir| mtime_ns(&dir))
+        .max()
+        .unwrap()
+    };
+    let subtree_key = subtree_a.to_string_lossy().to_string();
+    let cache_path = tmp.path().join("repo-index-cache.json");
+    let write_partial_cache = |mtime_ns: u64| {
+        std::fs::write(
+            &cache_path,
+            serde_json::json!({
+                "schema_version": 1,
+                "repos": [],
+                "complete": false,
+                "scanned": {
+                    &subtree_key: { "mtime_ns": mtime_ns, "repos": [] }
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+    };
+    let index = || {
+        let output = cargo_bin_cmd!("w")
+            .args([
+                "repo",
+                "index",
+                "--config",
+                config.to_str().unwrap(),
+                "--cache-path",
+                cache_path.to_str().unwrap(),
+            ])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w repo index failed: {output:?}");
+        let index: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(index["complete"], true);
+        index["repos"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["path"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    let repo_path = |repo: &Path| canonicalize(repo).unwrap().to_string_lossy().to_string();
+
+    write_partial_cache(subtree_mtime_ns());
+    assert_eq!(index(), [repo_path(&repo_b)]);
+
+    let cached: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&cache_path).unwrap()).unwrap();
+    assert_eq!(cached["complete"], true);
+    assert!(cached.get("scanned").is_none());
+
+    // `--refresh` doesn't trust the partial record at all.
+    write_partial_cache(subtree_mtime_ns());
+    let output = cargo_bin_cmd!("w")
+        .args([
+            "ls",
+            "--config",
+            config.to_str().unwrap(),
+            "--cache-path",
+            cache_path.to_str().unwrap(),
+            "--refresh",
+            "--format",
+            "json",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let listed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let mut repo_paths = listed["worktrees"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|wt| wt["repo_path"].as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    repo_paths.sort();
+    assert_eq!(repo_paths, [repo_path(&repo_a), repo_path(&repo_b)]);
+
+    // A repo appearing below the subtree's top directory invalidates the record too.
+    write_partial_cache(subtree_mtime_ns());
+    let repo_c = root.join("a/x/repo_c");
+    std::fs::create_dir_all(&repo_c).unwrap();
+    init_repo(&repo_c);
+    assert_eq!(
+        index(),
+        [repo_path(&repo_a), repo_path(&repo_c), repo_path(&repo_b)]
+    );
+}
+
+#[test]
+fn w_repo_index_treats_legacy_cache_as_complete() {
+    let tmp = tempfile::tempdir().unwrap();
+    let cache_path = tmp.path().join("repo-index-cache.json");
+    std::fs::write(&cache_path, r#"{"schema_version":1,"repos":[]}"#).unwrap();
+
+    let output = cargo_bin_cmd!("w")
+        .args([
+            "repo",
+            "index",
+            "--cached",
+            "--cache-path",
+            cache_path.to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w repo index failed: {output:?}");
+
+    let index: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(index["complete"], true);
+}
+
+#[test]
+fn w_repo_index_include_submodules_adds_parent_entries() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let sub_src = tmp.path().join("sub_src");
+    std::fs::create_dir_all(&sub_src).unwrap();
+    init_repo(&sub_src);
+
+    let root = tmp.path().join("root");
+    let parent = root.join("parent");
+    std::fs::create_dir_all(&parent).unwrap();
+    init_repo(&parent);
+    git(
+        &parent,
+        &[
+            "-c",
+            "protocol.file.allow=always",
+            "submodule",
+            "add",
+            sub_src.to_str().unwrap(),
+            "libs/sub",
+        ],
+    );
+    git(&parent, &["commit", "-m", "add submodule"]);
+
+    let cache_path = tmp.path().join("repo-index-cache.json");
+    let index_args = [
+        "repo",
+        "index",
+        "--root",
+        root.to_str().unwrap(),
+        "--max-depth",
+        "2",
+        "--cache-path",
+        cache_path.to_str().unwrap(),
+    ];
+
+    let output = cargo_bin_cmd!("w").args(index_args).output().unwrap();
+    assert!(output.status.success(), "w repo index failed: {output:?}");
+    let index: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(index["repos"].as_array().unwrap().len(), 1);
+
+    let output = cargo_bin_cmd!("w")
+        .args(index_args)
+        .arg("--include-submodules")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w repo index failed: {output:?}");
+
+    let expected_parent = canonicalize(&parent).unwrap().to_string_lossy().to_string();
+    let expected_sub = canonicalize(parent.join("libs/sub"))
+        .unwrap()
+        .to_string_lossy()
+        .to_string();
+    let index: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let repos = index["repos"].as_array().unwrap();
+    assert_eq!(repos.len(), 2);
+    assert_eq!(repos[0]["path"], expected_parent.as_str());
+    assert!(repos[0].get("parent").is_none());
+    assert_eq!(repos[1]["path"], expected_sub.as_str());
+    assert_eq!(repos[1]["parent"], expected_parent.as_str());
+
+    let output = cargo_bin_cmd!("w")
+        .args([
+            "ls",
+            "--cached",
+            "--cache-path",
+            cache_path.to_str().unwrap(),
+            "--format",
+            "json",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w ls failed: {output:?}");
+    let ls: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let paths = ls["worktrees"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|wt| wt["path"].as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert!(paths.contains(&expected_sub), "ls paths: {paths:?}");
+}
+
+#[test]
+fn w_repo_index_format_env_prints_a_shell_map() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let root = tmp.path().join("root");
+    let repo = root.join("repo a");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    let cache_path = tmp.path().join("repo-index-cache.json");
+
+    let index = |extra: &[&str]| {
+        cargo_bin_cmd!("w")
+            .args(["repo", "index", "--root", root.to_str().unwrap()])
+            .args([
+                "--max-depth",
+                "2",
+                "--cache-path",
+                cache_path.to_str().unwrap(),
+            ])
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+
+    let output = index(&["--format", "env", "--shell", "bash"]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let expected_path = canonicalize(&repo).unwrap().to_string_lossy().to_string();
+    assert!(stdout.starts_with("declare -gA W_REPOS=(\n"), "{stdout}");
+    assert!(
+        stdout.contains(&format!("]='{expected_path}'\n")),
+        "{stdout}"
+    );
+
+    let output = index(&["--format", "env", "--shell", "fish"]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains(&format!("set -g W_REPO_PATHS '{expected_path}'\n")),
+        "{stdout}"
+    );
+
+    let output = index(&["--format", "env"]);
+    assert!(!output.status.success(), "{output:?}");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--format env requires --shell"),
+        "{output:?}"
+    );
+
+    let output = index(&["--format", "tsv", "--shell", "zsh"]);
+    assert!(!output.status.success(), "{output:?}");
+}
@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+
+fn git(current_dir: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(current_dir)
+        .output()
+        .unwrap_or_else(|e| panic!("failed to run git {args:?}: {e}"));
+
+    if !output.status.success() {
+        panic!(
+            "git {args:?} failed\nstdout:\n{}\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// A clone of a bare `origin` with `feature` pushed and tracking `origin/feature`.
+fn clone_with_tracked_feature(tmp: &Path) -> (std::path::PathBuf, std::path::PathBuf) {
+    let origin = tmp.join("origin.git");
+    std::fs::create_dir_all(&origin).unwrap();
+    git(&origin, &["init", "--bare", "-b", "main"]);
+
+    let repo = tmp.join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    git(&repo, &["init", "-b", "main"]);
+    git(&repo, &["config", "user.name", "Test User"]);
+    git(&repo, &["config", "user.email", "test@example.com"]);
+    git(&repo, &["commit", "--allow-empty", "-m", "initial"]);
+    git(
+        &repo,
+        &["remote", "add", "origin", origin.to_str().unwrap()],
+    );
+    git(&repo, &["push", "origin", "main"]);
+    git(&repo, &["branch", "feature"]);
+    git(&repo, &["push", "-u", "origin", "feature"]);
+    (origin, repo)
+}
+
+#[test]
+fn w_retrack_follows_a_renamed_remote_branch() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (origin, repo) = clone_with_tracked_feature(tmp.path());
+
+    // Rename the branch on the remote, as a hosting UI would.
+    git(&origin, &["branch", "-m", "feature", "renamed"]);
+    git(&repo, &["commit", "--allow-empty", "-m", "local work"]);
+    git(&repo, &["branch", "-f", "feature", "HEAD"]);
+
+    let output = cargo_bin_cmd!("w")
+        .args([
+            "-C",
+            repo.to_str().unwrap(),
+            "retrack",
+            "feature",
+            "origin/renamed",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "w retrack failed: {output:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "feature -> origin/renamed (ahead 1, behind 0)\n"
+    );
+
+    assert_eq!(
+        git(&repo, &["config", "branch.feature.merge"]),
+        "refs/heads/renamed"
+    );
+    assert_eq!(git(&repo, &["config", "branch.feature.remote"]), "origin");
+    // The fetch pruned the old name's remote-tracking ref.
+    assert_eq!(
+        git(&repo, &["branch", "-r", "--list", "origin/feature"]),
+        ""
+    );
+}
+
+#[test]
+fn w_retrack_rejects_unknown_branch_and_missing_upstream() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (_origin, repo) = clone_with_tracked_feature(tmp.path());
+
+    let output = cargo_bin_cmd!("w")
+        .args([
+            "-C",
+            repo.to_str().unwrap(),
+            "retrack",
+            "nope",
+            "origin/main",
+        ])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("no local branch named nope"),
+        "{output:?}"
+    );
+
+    let output = cargo_bin_cmd!("w")
+        .args([
+            "-C",
+            repo.to_str().unwrap(),
+            "retrack",
+            "feature",
+            "origin/missing",
+        ])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("failed to set feature's upstream to origin/missing"),
+        "{output:?}"
+    );
+    assert_eq!(
+        git(&repo, &["config", "branch.feature.merge"]),
+        "refs/heads/feature"
+    );
+}
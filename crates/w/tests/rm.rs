@@ -85,6 +85,44 @@ fn w_rm_removes_clean_worktree() {
     assert!(local_branch_exists(tmp.path(), "feature"));
 }
 
+#[test]
+fn w_rm_reads_the_branch_from_a_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    init_repo(tmp.path());
+
+    let w = |args: &[&str]| {
+        cargo_bin_cmd!("w")
+            .current_dir(tmp.path())
+            .env(
+                "WORKTRUNK_WORKTREE_PATH",
+                ".worktrees/{{ branch | sanitize }}",
+            )
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let output_new = w(&["new", "feature"]);
+    assert!(output_new.status.success(), "w new failed: {output_new:?}");
+    let worktree_path = parse_path(&output_new.stdout);
+
+    let names = tmp.path().join("names.txt");
+    std::fs::write(&names, "feature\nother\n").unwrap();
+    let arg = format!("@{}", names.display());
+    let output = w(&["rm", &arg]);
+    assert!(!output.status.success(), "{output:?}");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("expected one branch name"),
+        "{output:?}"
+    );
+    assert!(worktree_path.exists());
+
+    std::fs::write(&names, "feature\n").unwrap();
+    let output_rm = w(&["rm", &arg]);
+    assert!(output_rm.status.success(), "w rm failed: {output_rm:?}");
+    assert_eq!(parse_path(&output_rm.stdout), worktree_path);
+    assert!(!worktree_path.exists());
+}
+
 #[test]
 fn w_rm_refuses_dirty_without_force_then_succeeds_with_force() {
     let tmp = tempfile::tempdir().unwrap();
@@ -152,3 +190,87 @@ fn w_rm_fails_for_missing_worktree() {
         .unwrap();
     assert!(!output_rm.status.success());
 }
+
+#[test]
+fn w_rm_all_merged_removes_only_clean_merged_worktrees() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+
+    let add_worktree = |branch: &str| {
+        let path = tmp.path().join(branch);
+        git(
+            &repo,
+            &["worktree", "add", "-b", branch, path.to_str().unwrap()],
+        );
+        dunce::canonicalize(path).unwrap()
+    };
+    let merged = add_worktree("merged");
+    git(&merged, &["commit", "--allow-empty", "-m", "merged work"]);
+    git(&repo, &["merge", "--ff-only", "merged"]);
+    let dirty = add_worktree("dirty");
+    std::fs::write(dirty.join("scratch.txt"), "wip\n").unwrap();
+    let unmerged = add_worktree("unmerged");
+    git(
+        &unmerged,
+        &["commit", "--allow-empty", "-m", "unmerged work"],
+    );
+    // Squash-merged: none of its commits are on main, but all of its changes are.
+    let squashed = add_worktree("squashed");
+    std::fs::write(squashed.join("feature.txt"), "feature\n").unwrap();
+    git(&squashed, &["add", "feature.txt"]);
+    git(&squashed, &["commit", "-m", "feature"]);
+    git(&repo, &["merge", "--squash", "squashed"]);
+    git(&repo, &["commit", "-m", "squash feature"]);
+    // Just branched off main, with nothing of its own yet: not merged, only new.
+    let fresh = add_worktree("fresh");
+
+    let rm = |extra: &[&str]| {
+        let mut args = vec!["rm", "--all-merged"];
+        args.extend_from_slice(extra);
+        let output = cargo_bin_cmd!("w")
+            .current_dir(&repo)
+            .args(&args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w rm failed: {output:?}");
+        output
+    };
+    let paths = |stdout: &[u8]| {
+        let mut paths = String::from_utf8(stdout.to_vec())
+            .unwrap()
+            .lines()
+            .map(PathBuf::from)
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths
+    };
+    let mut removable = vec![merged.clone(), squashed.clone()];
+    removable.sort();
+
+    let output = rm(&["--dry-run"]);
+    assert_eq!(paths(&output.stdout), removable);
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("skipping dirty worktree"),
+        "{output:?}"
+    );
+    assert!(merged.exists());
+
+    let output = rm(&["--delete-branch"]);
+    assert_eq!(paths(&output.stdout), removable);
+    assert!(!merged.exists());
+    assert!(!local_branch_exists(&repo, "merged"));
+    assert!(!squashed.exists());
+    assert!(fresh.exists());
+    assert!(dirty.exists());
+    assert!(unmerged.exists());
+    assert!(repo.exists());
+
+    let output = rm(&["--force"]);
+    assert_eq!(paths(&output.stdout), std::slice::from_ref(&dirty));
+    assert!(!dirty.exists());
+    assert!(local_branch_exists(&repo, "dirty"));
+    assert!(unmerged.exists());
+    assert!(fresh.exists());
+}
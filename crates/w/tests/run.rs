@@ -66,3 +66,53 @@ fn w_run_executes_in_worktree() {
         .expect("worktree path should exist");
     assert_eq!(top_level, expected);
 }
+
+#[cfg(unix)]
+#[test]
+fn w_run_retry_reruns_failing_command() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    let counter = tmp.path().join("attempts");
+
+    // Fails until its third run.
+    let script = format!(
+        "n=$(cat {0} 2>/dev/null || echo 0); n=$((n + 1)); echo $n > {0}; [ $n -ge 3 ]",
+        counter.display()
+    );
+    let run = |retry: &str| {
+        cargo_bin_cmd!("w")
+            .current_dir(&repo)
+            .env(
+                "WORKTRUNK_WORKTREE_PATH",
+                ".worktrees/{{ branch | sanitize }}",
+            )
+            .args(["run", "feature", "--retry", retry, "--retry-delay", "0.01"])
+            .args(["--", "sh", "-c", &script])
+            .output()
+            .unwrap()
+    };
+
+    let output = run("1");
+    assert_eq!(output.status.code(), Some(1), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("w run: exit code 1; retrying (1/2 attempts used)"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("w run: exit code 1 after 2 of up to 2 attempts"),
+        "{stderr}"
+    );
+
+    std::fs::remove_file(&counter).unwrap();
+    let output = run("5");
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("w run: exit code 0 after 3 of up to 6 attempts"),
+        "{stderr}"
+    );
+    assert_eq!(std::fs::read_to_string(&counter).unwrap().trim(), "3");
+}
@@ -0,0 +1,146 @@
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+/// A fake release in `dir`: `latest.json` plus an archive whose `w` prints "updated".
+///
+/// Returns the `file://` URL of `latest.json`. `checksum` overrides the published digest.
+fn fake_release(dir: &Path, tag: &str, checksum: Option<&str>) -> String {
+    let platform = match std::env::consts::OS {
+        "macos" => "macos",
+        _ => "linux",
+    };
+    let arch = match std::env::consts::ARCH {
+        "aarch64" => "ARM64",
+        _ => "X64",
+    };
+    let package = format!("w-{tag}-{platform}-{arch}");
+    std::fs::create_dir_all(dir.join(&package)).unwrap();
+    std::fs::write(dir.join(&package).join("w"), "#!/bin/sh\necho updated\n").unwrap();
+
+    let archive = dir.join(format!("{package}.tar.gz"));
+    let status = std::process::Command::new("tar")
+        .arg("-czf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(dir)
+        .arg(&package)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let digest = Sha256::digest(std::fs::read(&archive).unwrap())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    let checksum_file = dir.join(format!("{package}.tar.gz.sha256"));
+    std::fs::write(
+        &checksum_file,
+        format!("{}  {package}.tar.gz\n", checksum.unwrap_or(&digest)),
+    )
+    .unwrap();
+
+    let url = |path: &Path| format!("file://{}", path.display());
+    let release = serde_json::json!({
+        "tag_name": tag,
+        "assets": [
+            { "name": format!("{package}.tar.gz"), "browser_download_url": url(&archive) },
+            {
+                "name": format!("{package}.tar.gz.sha256"),
+                "browser_download_url": url(&checksum_file),
+            },
+        ],
+    });
+    let latest = dir.join("latest.json");
+    std::fs::write(&latest, release.to_string()).unwrap();
+    url(&latest)
+}
+
+/// A copy of the `w` binary that `self-update` is free to replace.
+fn installed_w(dir: &Path) -> PathBuf {
+    let bin_dir = dir.join("bin");
+    std::fs::create_dir_all(&bin_dir).unwrap();
+    let exe = bin_dir.join("w");
+    std::fs::copy(env!("CARGO_BIN_EXE_w"), &exe).unwrap();
+    exe
+}
+
+fn run(exe: &Path, release_url: &str, args: &[&str]) -> std::process::Output {
+    std::process::Command::new(exe)
+        .arg("self-update")
+        .args(args)
+        .env("W_SELF_UPDATE_RELEASE_URL", release_url)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn w_self_update_check_reports_without_replacing() {
+    let tmp = tempfile::tempdir().unwrap();
+    let release_url = fake_release(&tmp.path().join("release"), "v99.0.0", None);
+    let exe = installed_w(tmp.path());
+
+    let output = run(&exe, &release_url, &["--check"]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!(
+            "update available: w {} -> 99.0.0",
+            env!("CARGO_PKG_VERSION")
+        )),
+        "{stdout}"
+    );
+    assert_eq!(
+        std::fs::read(&exe).unwrap(),
+        std::fs::read(env!("CARGO_BIN_EXE_w")).unwrap()
+    );
+}
+
+#[test]
+fn w_self_update_replaces_binary_after_verifying_checksum() {
+    let tmp = tempfile::tempdir().unwrap();
+    let release_url = fake_release(&tmp.path().join("release"), "v99.0.0", None);
+    let exe = installed_w(tmp.path());
+
+    let output = run(&exe, &release_url, &[]);
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Verifying checksum"), "{stderr}");
+
+    let output = std::process::Command::new(&exe).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "updated\n");
+}
+
+#[test]
+fn w_self_update_refuses_a_checksum_mismatch() {
+    let tmp = tempfile::tempdir().unwrap();
+    let release_url = fake_release(
+        &tmp.path().join("release"),
+        "v99.0.0",
+        Some(&"0".repeat(64)),
+    );
+    let exe = installed_w(tmp.path());
+
+    let output = run(&exe, &release_url, &[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("checksum mismatch"), "{stderr}");
+    assert_eq!(
+        std::fs::read(&exe).unwrap(),
+        std::fs::read(env!("CARGO_BIN_EXE_w")).unwrap()
+    );
+}
+
+#[test]
+fn w_self_update_is_a_no_op_when_up_to_date() {
+    let tmp = tempfile::tempdir().unwrap();
+    let release_url = fake_release(&tmp.path().join("release"), "v0.0.1", None);
+    let exe = installed_w(tmp.path());
+
+    let output = run(&exe, &release_url, &[]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("is up to date"), "{stdout}");
+}
@@ -0,0 +1,94 @@
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+
+fn git(current_dir: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(current_dir)
+        .output()
+        .unwrap_or_else(|e| panic!("failed to run git {args:?}: {e}"));
+
+    if !output.status.success() {
+        panic!(
+            "git {args:?} failed\nstdout:\n{}\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn init_repo(repo_dir: &Path) {
+    git(repo_dir, &["init", "-b", "main"]);
+    git(repo_dir, &["config", "user.name", "Test User"]);
+    git(repo_dir, &["config", "user.email", "test@example.com"]);
+
+    std::fs::write(repo_dir.join("README.md"), "hello\n").unwrap();
+    git(repo_dir, &["add", "README.md"]);
+    git(repo_dir, &["commit", "-m", "initial"]);
+}
+
+#[test]
+fn w_set_description_sets_and_clears() {
+    let tmp = tempfile::tempdir().unwrap();
+    init_repo(tmp.path());
+    git(tmp.path(), &["branch", "feature"]);
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(tmp.path())
+        .args(["set-description", "feature", "Long-lived spike"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "w set-description failed: {output:?}"
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "Set description for feature"
+    );
+    assert_eq!(
+        git(tmp.path(), &["config", "branch.feature.description"]).trim(),
+        "Long-lived spike"
+    );
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(tmp.path())
+        .args(["set-description", "feature", ""])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "w set-description failed: {output:?}"
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "Cleared description for feature"
+    );
+
+    let status = std::process::Command::new("git")
+        .args(["config", "branch.feature.description"])
+        .current_dir(tmp.path())
+        .status()
+        .unwrap();
+    assert!(!status.success(), "description should be unset");
+}
+
+#[test]
+fn w_set_description_fails_for_missing_branch() {
+    let tmp = tempfile::tempdir().unwrap();
+    init_repo(tmp.path());
+
+    let output = cargo_bin_cmd!("w")
+        .current_dir(tmp.path())
+        .args(["set-description", "nope", "text"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no local branch named nope"),
+        "stderr did not mention the branch:\n{stderr}"
+    );
+}
@@ -59,3 +59,206 @@ fn w_shell_init_pwsh_prints_snippet() {
     assert!(stdout.contains("Set-Location"));
     assert!(stdout.contains("--print"));
 }
+
+#[test]
+fn w_shell_init_without_shell_uses_shell_env() {
+    // The test harness (not a shell) is the parent process, so `$SHELL` decides.
+    let output = cargo_bin_cmd!("w")
+        .args(["shell", "init"])
+        .env("SHELL", "/usr/bin/fish")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("w shell init fish | source"), "{stdout}");
+
+    let output = cargo_bin_cmd!("w")
+        .args(["shell", "init"])
+        .env("SHELL", "/bin/tcsh")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("could not detect the current shell"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn w_shell_init_nu_prints_snippet() {
+    let (code, stdout, stderr) = shell_init("nu");
+    assert_eq!(code, 0, "stderr:\n{stderr}");
+    assert!(!stdout.is_empty());
+    assert!(!stdout.contains("TODO"));
+    assert!(stdout.contains("w shell init nu | save"));
+    assert!(stdout.contains("def --env --wrapped w"));
+    assert!(stdout.contains("cd $target"));
+    assert!(stdout.contains("--print"));
+}
+
+#[test]
+fn w_shell_init_posix_avoids_bash_only_syntax() {
+    let (code, stdout, stderr) = shell_init("posix");
+    assert_eq!(code, 0, "stderr:\n{stderr}");
+    assert!(stdout.contains("eval \"$(w shell init posix)\""));
+    assert!(stdout.contains("w() {"));
+    assert!(stdout.contains("command w"));
+    assert!(stdout.contains("--print"));
+
+    let code_lines = stdout
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n");
+    for bashism in [
+        "[[",
+        "]]",
+        "local ",
+        "function ",
+        "$'",
+        "==",
+        "<(",
+        "source ",
+    ] {
+        assert!(
+            !code_lines.contains(bashism),
+            "posix snippet uses {bashism:?}:\n{code_lines}"
+        );
+    }
+}
+
+/// Find `name` on `PATH`, so tests for shells that aren't installed can be skipped.
+#[cfg(unix)]
+fn find_shell(name: &str) -> Option<std::path::PathBuf> {
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Run `script` with `shell -c` in a repo with a `feature` branch, `w` on `PATH`, and
+/// worktrees placed by `worktree_template`, returning the directory it printed last and
+/// the directory `feature`'s worktree was expected at.
+#[cfg(unix)]
+fn cd_through_wrapper(
+    shell: &std::path::Path,
+    script: &str,
+    worktree_template: &str,
+    expected_worktree: &str,
+) -> (std::path::PathBuf, std::path::PathBuf) {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(repo)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    };
+    git(&["init", "-b", "main"]);
+    git(&["config", "user.name", "Test User"]);
+    git(&["config", "user.email", "test@example.com"]);
+    git(&["commit", "--allow-empty", "-m", "initial"]);
+    git(&["branch", "feature"]);
+
+    let bin_dir = std::path::Path::new(env!("CARGO_BIN_EXE_w"))
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    let path = std::env::join_paths(
+        std::iter::once(bin_dir).chain(std::env::split_paths(&std::env::var_os("PATH").unwrap())),
+    )
+    .unwrap();
+
+    let output = std::process::Command::new(shell)
+        .args(["-c", script])
+        .current_dir(repo)
+        .env("PATH", path)
+        .env("WORKTRUNK_WORKTREE_PATH", worktree_template)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{shell:?}: {output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let pwd = stdout.strip_suffix('\n').unwrap_or(&stdout);
+    let expected = dunce::canonicalize(repo.join(expected_worktree)).unwrap();
+    (std::path::PathBuf::from(pwd), expected)
+}
+
+#[cfg(unix)]
+#[test]
+fn w_shell_init_posix_changes_directory_in_sh() {
+    let sh = std::path::Path::new("/bin/sh");
+    if !sh.exists() {
+        return;
+    }
+
+    let (pwd, expected) = cd_through_wrapper(
+        sh,
+        r#"eval "$(w shell init posix)" && w cd feature && pwd -P"#,
+        ".worktrees/{{ branch | sanitize }}",
+        ".worktrees/feature",
+    );
+    assert_eq!(pwd, expected);
+}
+
+/// `w cd feature` through each shell's wrapper, with worktrees placed by `template`,
+/// must land in `expected_worktree`.
+///
+/// Shells that aren't installed are skipped with a notice on stderr, written directly so
+/// the test harness doesn't capture it.
+#[cfg(unix)]
+fn assert_wrappers_cd_into(template: &str, expected_worktree: &str) {
+    use std::io::Write as _;
+
+    let scripts = [
+        (
+            "sh",
+            r#"eval "$(w shell init posix)" && w cd feature && pwd -P"#,
+        ),
+        (
+            "bash",
+            r#"eval "$(w shell init bash)" && w cd feature && pwd -P"#,
+        ),
+        (
+            "zsh",
+            r#"eval "$(w shell init zsh)" && w cd feature && pwd -P"#,
+        ),
+        (
+            "fish",
+            "w shell init fish | source; and w cd feature; and pwd -P",
+        ),
+    ];
+
+    for (name, script) in scripts {
+        let Some(shell) = find_shell(name) else {
+            let _ = writeln!(
+                std::io::stderr(),
+                "skipping the {name} wrapper for {template:?}: {name} isn't on PATH"
+            );
+            continue;
+        };
+        let (pwd, expected) = cd_through_wrapper(&shell, script, template, expected_worktree);
+        assert_eq!(pwd, expected, "{name}");
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn w_shell_wrappers_cd_into_paths_with_spaces_and_leading_dashes() {
+    // A space in two components, and one starting with a dash.
+    assert_wrappers_cd_into(
+        ".worktrees/-w trees/{{ branch | sanitize }} dir",
+        ".worktrees/-w trees/feature dir",
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn w_shell_wrappers_cd_into_paths_with_newlines() {
+    // `$(...)` has to keep an inner newline, and fish mustn't split its output on it.
+    assert_wrappers_cd_into(
+        ".worktrees/new\nline/{{ branch | sanitize }}",
+        ".worktrees/new\nline/feature",
+    );
+}
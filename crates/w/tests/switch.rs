@@ -148,3 +148,251 @@ fn w_switch_without_filter_requires_tty() {
     #[cfg(not(windows))]
     assert!(stderr.contains("interactive picker requires a TTY"));
 }
+
+#[test]
+fn w_switch_tmux_requires_tmux_session() {
+    let tmp = tempfile::tempdir().unwrap();
+    init_repo(tmp.path());
+
+    let output = cargo_bin_cmd!("w")
+        .env_remove("TMUX")
+        .args([
+            "-C",
+            tmp.path().to_str().unwrap(),
+            "switch",
+            "--filter",
+            "main",
+            "--tmux",
+        ])
+        .output()
+        .unwrap();
+    assert!(
+        !output.status.success(),
+        "w switch --tmux should fail outside tmux"
+    );
+    assert!(output.stdout.is_empty());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("inside a tmux session"), "stderr: {stderr}");
+}
+
+#[test]
+fn w_switch_history_lists_recent_worktrees_most_recent_first() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+
+    let wt_a = tmp.path().join("worktree_feature_a");
+    let wt_b = tmp.path().join("worktree_feature_b");
+    git(
+        &repo,
+        &["worktree", "add", "-b", "feature-a", wt_a.to_str().unwrap()],
+    );
+    git(
+        &repo,
+        &["worktree", "add", "-b", "feature-b", wt_b.to_str().unwrap()],
+    );
+
+    let cache_home = tmp.path().join("cache");
+    let w = || {
+        let mut cmd = cargo_bin_cmd!("w");
+        cmd.env("XDG_CACHE_HOME", &cache_home);
+        cmd
+    };
+    let switch_to = |filter: &str| {
+        let output = w()
+            .args(["-C", repo.to_str().unwrap(), "switch", "--filter", filter])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w switch failed: {output:?}");
+    };
+    let history = || {
+        let output = w()
+            .args(["switch", "--history", "--print"])
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "w switch --history failed: {output:?}"
+        );
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(PathBuf::from)
+            .collect::<Vec<_>>()
+    };
+
+    switch_to("feature-a");
+    switch_to("feature-b");
+    switch_to("feature-a");
+
+    let wt_a = canonicalize(&wt_a).unwrap();
+    let wt_b = canonicalize(&wt_b).unwrap();
+    assert_eq!(history(), [wt_a.clone(), wt_b.clone()]);
+
+    let output = w()
+        // Matched case-insensitively, like `--filter` without `--history`.
+        .args(["switch", "--history", "--filter", "FEATURE_B"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "w switch --history failed: {output:?}"
+    );
+    assert_eq!(parse_path(&output.stdout), wt_b);
+    assert_eq!(history(), [wt_b.clone(), wt_a.clone()]);
+
+    // Vanished worktrees are pruned when the list is built.
+    git(
+        &repo,
+        &["worktree", "remove", "--force", wt_b.to_str().unwrap()],
+    );
+    assert_eq!(history(), [wt_a]);
+}
+
+#[test]
+fn w_switch_history_errors_when_empty() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let output = cargo_bin_cmd!("w")
+        .env("XDG_CACHE_HOME", tmp.path())
+        .args(["switch", "--history", "--filter", "anything"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success(), "expected failure: {output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no recently visited worktrees"),
+        "unexpected stderr:\n{stderr}"
+    );
+}
+
+#[test]
+fn w_switch_exec_runs_command_in_selected_worktree() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = tmp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+
+    let wt = tmp.path().join("worktree_feature");
+    git(
+        &repo,
+        &["worktree", "add", "-b", "feature", wt.to_str().unwrap()],
+    );
+
+    let output = cargo_bin_cmd!("w")
+        .env("XDG_CACHE_HOME", tmp.path().join("cache"))
+        .args([
+            "-C",
+            repo.to_str().unwrap(),
+            "switch",
+            "--filter",
+            "feature",
+            "--exec",
+            "--",
+            "sh",
+            "-c",
+            "pwd; exit 3",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3), "{output:?}");
+    // The command's output replaces the path the shell wrapper would `cd` to.
+    assert_eq!(parse_path(&output.stdout), canonicalize(&wt).unwrap());
+
+    let output = cargo_bin_cmd!("w")
+        .args([
+            "-C",
+            repo.to_str().unwrap(),
+            "switch",
+            "--filter",
+            "feature",
+            "--",
+            "pwd",
+        ])
+        .output()
+        .unwrap();
+    assert!(!output.status.success(), "expected failure: {output:?}");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("requires --exec"),
+        "{output:?}"
+    );
+}
+
+#[test]
+fn w_switch_allow_empty_exits_zero_when_there_is_nothing_to_pick() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+
+    let run = |extra: &[&str]| {
+        cargo_bin_cmd!("w")
+            .args(["switch", "--root", root.to_str().unwrap()])
+            .arg("--cache-path")
+            .arg(tmp.path().join("repo-index.json"))
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&["--filter", "anything"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("no worktrees found"), "{stderr}");
+
+    let output = run(&["--filter", "anything", "--allow-empty"]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(output.stdout.is_empty(), "{output:?}");
+
+    // A filter that matches nothing among existing worktrees is still an error.
+    let repo = root.join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    init_repo(&repo);
+    let output = run(&["--refresh", "--filter", "no-such-branch", "--allow-empty"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no worktree matched filter: no-such-branch"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn w_switch_two_stage_is_bypassed_by_filter() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    let root = tmp.path().join("root");
+    let repo_a = root.join("repo_a");
+    let repo_b = root.join("repo_b");
+    std::fs::create_dir_all(&repo_a).unwrap();
+    std::fs::create_dir_all(&repo_b).unwrap();
+    init_repo(&repo_a);
+    init_repo(&repo_b);
+
+    let wt_b = tmp.path().join("worktree_feature_b");
+    git(
+        &repo_b,
+        &["worktree", "add", "-b", "feature-b", wt_b.to_str().unwrap()],
+    );
+
+    let config = tmp.path().join("config.toml");
+    std::fs::write(&config, "[picker]\ntwo_stage = true\n").unwrap();
+    let cache_path = tmp.path().join("repo-index-cache.json");
+
+    for extra in [
+        &["--two-stage"][..],
+        &["--config", config.to_str().unwrap()],
+    ] {
+        let output = cargo_bin_cmd!("w")
+            .args(["switch", "--root", root.to_str().unwrap()])
+            .args(["--max-depth", "2", "--cache-path"])
+            .arg(&cache_path)
+            .args(extra)
+            .args(["--filter", "feature-b"])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "w switch failed: {output:?}");
+        assert_eq!(parse_path(&output.stdout), canonicalize(&wt_b).unwrap());
+    }
+}